@@ -1,46 +1,49 @@
-use super::Outcome;
-use crate::{
-    maze::Maze,
-    movement::MazeEvent,
-    ui::{self, RoomView},
-};
+use super::{Outcome, board::Board};
+use crate::{maze::MoveError, movement::MazeEvent, ui};
 use color_eyre::Result;
 use crossterm::event;
-use multid::iterators::V2Indices;
 use ratatui::{
     DefaultTerminal, Frame,
     buffer::Buffer,
     layout::Rect,
     widgets::{StatefulWidget, Widget, canvas::Canvas},
 };
+use std::marker::PhantomData;
 
-pub struct BasicGame<const N_ROWS: usize, const N_COLS: usize>;
+pub struct BasicGame<B> {
+    _marker: PhantomData<B>,
+}
 
-impl<const N_ROWS: usize, const N_COLS: usize> StatefulWidget for BasicGame<N_ROWS, N_COLS> {
-    type State = Maze<N_ROWS, N_COLS>;
+impl<B> BasicGame<B> {
+    fn new() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<B: Board> StatefulWidget for BasicGame<B> {
+    type State = B;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let (x_bounds, y_bounds) = state.bounds();
         let c = Canvas::default()
-            .x_bounds([ui::MIN_X, ui::MAX_X])
-            .y_bounds([ui::MIN_Y, ui::MAX_Y])
+            .x_bounds(x_bounds)
+            .y_bounds(y_bounds)
             .background_color(ui::BG_COLOR)
             .paint(move |ctx| {
-                for ix in V2Indices::<N_ROWS, N_COLS>::new() {
-                    let room = &state.rooms[ix];
-                    let view = RoomView {
-                        x: -200.0 + ui::ROOM_SIZE * ix.x() as f64,
-                        y: 200.0 - ui::ROOM_SIZE * ix.y() as f64,
-                        room,
-                    };
-                    ctx.draw(&view);
-                    let label_x = -200.0 + (ui::ROOM_SIZE * ix.x() as f64) + ui::SEG_LEN * 3.5;
-                    let label_y = 200.0 - (ui::ROOM_SIZE * ix.y() as f64 + ui::SEG_LEN * 3.5);
-                    if ix == state.current_ix && ix == state.goal {
-                        ctx.print(label_x, label_y, "\u{1f940}")
-                    } else if ix == state.current_ix {
-                        ctx.print(label_x, label_y, "\u{1f600}")
-                    } else if ix == state.goal {
-                        ctx.print(label_x, label_y, "\u{1f945}")
+                for ix in state.rooms() {
+                    state.draw_room(ctx, ix);
+                    for (label_x, label_y) in state.labels_at(ix) {
+                        if ix == state.current() && ix == state.goal() {
+                            ctx.print(label_x, label_y, "\u{1f940}")
+                        } else if ix == state.current() {
+                            ctx.print(label_x, label_y, "\u{1f600}")
+                        } else if ix == state.goal() {
+                            ctx.print(label_x, label_y, "\u{1f945}")
+                        } else if let Some(item) = state.item_at(ix) {
+                            ctx.print(label_x, label_y, item)
+                        }
                     }
                 }
             });
@@ -48,24 +51,25 @@ impl<const N_ROWS: usize, const N_COLS: usize> StatefulWidget for BasicGame<N_RO
     }
 }
 
-pub fn game<const N_ROWS: usize, const N_COLS: usize>(
-    terminal: &mut DefaultTerminal,
-    maze: &mut Maze<N_ROWS, N_COLS>,
-) -> Result<Outcome> {
+pub fn game<B: Board>(terminal: &mut DefaultTerminal, maze: &mut B, info: &str) -> Result<Outcome> {
+    let mut blocked: Option<MoveError> = None;
     loop {
+        let status = ui::status(&maze.info(info), blocked);
         terminal.draw(|frame: &mut Frame| {
-            frame.render_stateful_widget(BasicGame {}, frame.area(), maze)
+            let [maze_area, footer_area] = ui::footer_layout(frame.area());
+            frame.render_stateful_widget(BasicGame::new(), maze_area, maze);
+            frame.render_widget(ui::footer(&status), footer_area);
         })?;
         if maze.is_done() {
             return Ok(Outcome::Win);
         }
         match event::read()?.into() {
-            MazeEvent::MoveN => maze.move_north(),
-            MazeEvent::MoveS => maze.move_south(),
-            MazeEvent::MoveE => maze.move_east(),
-            MazeEvent::MoveW => maze.move_west(),
             MazeEvent::Quit => return Ok(Outcome::Quit),
-            _ => false,
+            ev => {
+                if let Some(result) = maze.step(&ev) {
+                    blocked = result.err();
+                }
+            }
         };
     }
 }
@@ -0,0 +1,359 @@
+use crate::{
+    Direction,
+    grid::Ix,
+    maze::{
+        Maze, MoveResult, Room,
+        floors::{FloorPos, Floors},
+        hex::{HexDirection, HexMaze, hex_neighbor},
+        polar::{PolarDirection, PolarMaze},
+    },
+    movement::MazeEvent,
+    ui::{
+        self, HexRoomView, PolarRoomView, RoomView, UnseenHexView, UnseenPolarView, UnseenRoomView,
+    },
+};
+use ratatui::{
+    style::Stylize,
+    text::{Line, Span},
+    widgets::canvas::Context,
+};
+
+/// What the basic, hidden and lantern modes need to draw and play a maze,
+/// whatever shape its rooms are.
+pub trait Board {
+    /// Where a room is. Rooms the player has seen are remembered by this.
+    type Pos: Copy + Ord;
+
+    fn current(&self) -> Self::Pos;
+    fn goal(&self) -> Self::Pos;
+    fn is_done(&self) -> bool {
+        self.current() == self.goal()
+    }
+    /// Every room there is to draw.
+    fn rooms(&self) -> Vec<Self::Pos>;
+    /// Canvas bounds that fit the whole maze.
+    fn bounds(&self) -> ([f64; 2], [f64; 2]);
+    /// Where the player or goal is printed in room `ix`.
+    fn label_at(&self, ix: Self::Pos) -> (f64, f64);
+    /// Like `label_at`, for every place room `ix` is drawn.
+    fn labels_at(&self, ix: Self::Pos) -> Vec<(f64, f64)> {
+        vec![self.label_at(ix)]
+    }
+    /// Something lying in room `ix`, printed where the player or goal would be
+    /// when neither is there.
+    fn item_at(&self, _ix: Self::Pos) -> Option<Line<'static>> {
+        None
+    }
+    fn draw_room(&self, ctx: &mut Context, ix: Self::Pos);
+    /// Draws the walls of an unseen room that face the edge or another room
+    /// that isn't `seen`.
+    fn draw_unseen(&self, ctx: &mut Context, ix: Self::Pos, seen: &dyn Fn(Self::Pos) -> bool);
+    /// The rooms a lantern carried into `ix` lights up, `ix` included.
+    fn lit(&self, ix: Self::Pos) -> Vec<Self::Pos>;
+    /// Moves the player, if `ev` is a move this shape of maze has a direction for.
+    fn step(&mut self, ev: &MazeEvent) -> Option<MoveResult>;
+    /// The footer text, given the game's `info` about the maze.
+    fn info(&self, info: &str) -> String {
+        info.to_string()
+    }
+}
+
+impl Board for Maze {
+    type Pos = Ix;
+
+    fn current(&self) -> Ix {
+        self.current_ix
+    }
+    fn goal(&self) -> Ix {
+        self.goal
+    }
+    fn rooms(&self) -> Vec<Ix> {
+        self.mask.active_rooms().collect()
+    }
+    /// Rooms next to an edge that wraps are drawn again just past the opposite
+    /// edge, so there's a border of them around the maze, and the lantern can
+    /// light the rooms across the edge right next to the player.
+    fn bounds(&self) -> ([f64; 2], [f64; 2]) {
+        let ([x0, x1], [y0, y1]) = ui::maze_bounds(self.size());
+        let border = |wraps: bool| if wraps { ui::ROOM_SIZE } else { 0.0 };
+        let dx = border(self.wrap.wraps_cols(self.size()));
+        let dy = border(self.wrap.wraps_rows(self.size()));
+        ([x0 - dx, x1 + dx], [y0 - dy, y1 + dy])
+    }
+    fn label_at(&self, ix: Ix) -> (f64, f64) {
+        label(corner(ix.y() as isize, ix.x() as isize))
+    }
+    fn labels_at(&self, ix: Ix) -> Vec<(f64, f64)> {
+        self.wrap
+            .copies(ix)
+            .into_iter()
+            .map(|(row, col, _)| label(corner(row, col)))
+            .collect()
+    }
+    fn item_at(&self, ix: Ix) -> Option<Line<'static>> {
+        self.keys.get(&ix).map(|&key| ui::key_label(key))
+    }
+    fn draw_room(&self, ctx: &mut Context, ix: Ix) {
+        for (row, col, flipped) in self.wrap.copies(ix) {
+            let (x, y) = corner(row, col);
+            let room = &if flipped {
+                upside_down(self.room(ix))
+            } else {
+                self.room(ix)
+            };
+            ctx.draw(&RoomView { x, y, room });
+        }
+    }
+    fn draw_unseen(&self, ctx: &mut Context, ix: Ix, seen: &dyn Fn(Ix) -> bool) {
+        let hidden_walls: Vec<Direction> = Direction::North
+            .into_iter()
+            .filter(|&dir| self.neighbor(ix, dir).is_none_or(|i| !seen(i)))
+            .collect();
+        for (row, col, flipped) in self.wrap.copies(ix) {
+            let (x, y) = corner(row, col);
+            let hidden_walls = hidden_walls
+                .iter()
+                .map(|&dir| match dir {
+                    Direction::North | Direction::South if flipped => dir.opposite(),
+                    dir => dir,
+                })
+                .collect();
+            ctx.draw(&UnseenRoomView { x, y, hidden_walls });
+        }
+    }
+    fn lit(&self, ix: Ix) -> Vec<Ix> {
+        let (row, col) = (ix.y() as isize, ix.x() as isize);
+        let mut lit: Vec<Ix> = Vec::new();
+        for (r, c) in (row - 1..=row + 1).flat_map(|r| (col - 1..=col + 1).map(move |c| (r, c))) {
+            if let Some(i) = self.wrap.resolve(self.size(), r, c)
+                && i != ix
+                && self.is_active(i)
+                && !lit.contains(&i)
+            {
+                lit.push(i);
+            }
+        }
+        lit.push(ix);
+        lit
+    }
+    fn step(&mut self, ev: &MazeEvent) -> Option<MoveResult> {
+        ev.direction().map(|dir| self.try_move(dir))
+    }
+    /// Mazes with locked doors also say which keys the player is holding.
+    fn info(&self, info: &str) -> String {
+        if !self.has_keys() {
+            info.to_string()
+        } else if self.held.is_empty() {
+            format!("no keys | {info}")
+        } else {
+            let held: Vec<String> = self.held.iter().map(|key| key.to_string()).collect();
+            format!("keys: {} | {info}", held.join(" "))
+        }
+    }
+}
+
+/// The top left corner of the square room at `row`, `col` on the canvas,
+/// which can be just past the edge of the grid.
+fn corner(row: isize, col: isize) -> (f64, f64) {
+    (
+        ui::MIN_X + ui::ROOM_SIZE * col as f64,
+        ui::MAX_Y - ui::ROOM_SIZE * row as f64,
+    )
+}
+
+fn label((x, y): (f64, f64)) -> (f64, f64) {
+    (x + ui::SEG_LEN * 3.5, y - ui::SEG_LEN * 3.5)
+}
+
+/// `room` as it's seen across the half twist of a Möbius strip.
+fn upside_down(mut room: Room) -> Room {
+    std::mem::swap(&mut room.doors.north, &mut room.doors.south);
+    room.wrapped
+        .swap(Direction::North as usize, Direction::South as usize);
+    room
+}
+
+impl Board for HexMaze {
+    type Pos = Ix;
+
+    fn current(&self) -> Ix {
+        self.current_ix
+    }
+    fn goal(&self) -> Ix {
+        self.goal
+    }
+    fn rooms(&self) -> Vec<Ix> {
+        self.size().indices().collect()
+    }
+    fn bounds(&self) -> ([f64; 2], [f64; 2]) {
+        ui::hex_bounds(self.size())
+    }
+    fn label_at(&self, ix: Ix) -> (f64, f64) {
+        let (x, y) = ui::hex_centre(ix);
+        // emoji are printed from their left edge
+        (x - ui::SEG_LEN, y)
+    }
+    fn draw_room(&self, ctx: &mut Context, ix: Ix) {
+        let (x, y) = ui::hex_centre(ix);
+        ctx.draw(&HexRoomView {
+            x,
+            y,
+            doors: self.doors(ix),
+        });
+    }
+    fn draw_unseen(&self, ctx: &mut Context, ix: Ix, seen: &dyn Fn(Ix) -> bool) {
+        let (x, y) = ui::hex_centre(ix);
+        let hidden_walls: Vec<HexDirection> = HexDirection::ALL
+            .into_iter()
+            .filter(|&dir| hex_neighbor(ix, dir).is_none_or(|i| !seen(i)))
+            .collect();
+        ctx.draw(&UnseenHexView { x, y, hidden_walls });
+    }
+    fn lit(&self, ix: Ix) -> Vec<Ix> {
+        HexDirection::ALL
+            .into_iter()
+            .filter_map(|dir| hex_neighbor(ix, dir))
+            .chain(std::iter::once(ix))
+            .collect()
+    }
+    fn step(&mut self, ev: &MazeEvent) -> Option<MoveResult> {
+        ev.hex_direction().map(|dir| self.try_move(dir))
+    }
+}
+
+impl Board for PolarMaze {
+    type Pos = Ix;
+
+    fn current(&self) -> Ix {
+        self.current_ix
+    }
+    fn goal(&self) -> Ix {
+        self.goal
+    }
+    fn rooms(&self) -> Vec<Ix> {
+        PolarMaze::rooms(self).collect()
+    }
+    fn bounds(&self) -> ([f64; 2], [f64; 2]) {
+        ui::polar_bounds(self.rings())
+    }
+    fn label_at(&self, ix: Ix) -> (f64, f64) {
+        let (x, y) = ui::polar_centre(ix.y(), self.ring_len(ix.y()), ix.x());
+        (x - ui::SEG_LEN, y)
+    }
+    fn draw_room(&self, ctx: &mut Context, ix: Ix) {
+        ctx.draw(&PolarRoomView {
+            ring: ix.y(),
+            len: self.ring_len(ix.y()),
+            cell: ix.x(),
+            outward: self.outward_count(ix.y()),
+            doors: self.doors(ix),
+        });
+    }
+    fn draw_unseen(&self, ctx: &mut Context, ix: Ix, seen: &dyn Fn(Ix) -> bool) {
+        let hidden_walls: Vec<PolarDirection> = self
+            .directions(ix)
+            .into_iter()
+            .filter(|&dir| self.neighbor(ix, dir).is_none_or(|i| !seen(i)))
+            .collect();
+        ctx.draw(&UnseenPolarView {
+            ring: ix.y(),
+            len: self.ring_len(ix.y()),
+            cell: ix.x(),
+            outward: self.outward_count(ix.y()),
+            hidden_walls,
+        });
+    }
+    fn lit(&self, ix: Ix) -> Vec<Ix> {
+        self.directions(ix)
+            .into_iter()
+            .filter_map(|dir| self.neighbor(ix, dir))
+            .chain(std::iter::once(ix))
+            .collect()
+    }
+    fn step(&mut self, ev: &MazeEvent) -> Option<MoveResult> {
+        let ring = self.current_ix.y();
+        let dir = match ev {
+            MazeEvent::MoveN => PolarDirection::Inward,
+            MazeEvent::MoveE => PolarDirection::Clockwise,
+            MazeEvent::MoveW => PolarDirection::Anticlockwise,
+            MazeEvent::MoveS => self.way_out(self.current_ix),
+            // where the next ring out splits a room, these pick which half to go to
+            MazeEvent::MoveSW => PolarDirection::Outward(0),
+            MazeEvent::MoveSE => {
+                PolarDirection::Outward(self.outward_count(ring).saturating_sub(1))
+            }
+            _ => return None,
+        };
+        Some(self.try_move(dir))
+    }
+}
+
+/// Only the player's floor is drawn. Stairs up are marked `<` in the top left of
+/// a room and stairs down `>` in the bottom right, after the keys that take them.
+impl Board for Floors {
+    type Pos = FloorPos;
+
+    fn current(&self) -> FloorPos {
+        Floors::current(self)
+    }
+    fn goal(&self) -> FloorPos {
+        self.goal
+    }
+    fn rooms(&self) -> Vec<FloorPos> {
+        self.floors[self.floor]
+            .mask
+            .active_rooms()
+            .map(|ix| (self.floor, ix))
+            .collect()
+    }
+    fn bounds(&self) -> ([f64; 2], [f64; 2]) {
+        self.floors[self.floor].bounds()
+    }
+    fn label_at(&self, (floor, ix): FloorPos) -> (f64, f64) {
+        self.floors[floor].label_at(ix)
+    }
+    fn labels_at(&self, (floor, ix): FloorPos) -> Vec<(f64, f64)> {
+        self.floors[floor].labels_at(ix)
+    }
+    fn item_at(&self, (floor, ix): FloorPos) -> Option<Line<'static>> {
+        self.floors[floor].item_at(ix)
+    }
+    fn draw_room(&self, ctx: &mut Context, pos: FloorPos) {
+        let (floor, ix) = pos;
+        self.floors[floor].draw_room(ctx, ix);
+        let (x, y) = corner(ix.y() as isize, ix.x() as isize);
+        if self.has_stairs_up(pos) {
+            let (x, y) = (x + ui::SEG_LEN, y - ui::SEG_LEN);
+            ctx.print(x, y, Span::raw("<").fg(ui::STAIRS_COLOR));
+        }
+        if self.has_stairs_down(pos) {
+            let (x, y) = (x + ui::SEG_LEN * 5.5, y - ui::SEG_LEN * 5.5);
+            ctx.print(x, y, Span::raw(">").fg(ui::STAIRS_COLOR));
+        }
+    }
+    fn draw_unseen(
+        &self,
+        ctx: &mut Context,
+        (floor, ix): FloorPos,
+        seen: &dyn Fn(FloorPos) -> bool,
+    ) {
+        self.floors[floor].draw_unseen(ctx, ix, &|i| seen((floor, i)));
+    }
+    fn lit(&self, (floor, ix): FloorPos) -> Vec<FloorPos> {
+        self.floors[floor]
+            .lit(ix)
+            .into_iter()
+            .map(|i| (floor, i))
+            .collect()
+    }
+    fn step(&mut self, ev: &MazeEvent) -> Option<MoveResult> {
+        match ev {
+            MazeEvent::Up => Some(self.climb(true)),
+            MazeEvent::Down => Some(self.climb(false)),
+            ev => ev.direction().map(|dir| self.here().try_move(dir)),
+        }
+    }
+    fn info(&self, info: &str) -> String {
+        format!("floor {}/{} | {info}", self.floor + 1, self.floors.len())
+    }
+}
@@ -0,0 +1,284 @@
+use super::{
+    Generator, Seeder,
+    seeders::{braid, braid_down_to},
+};
+use crate::maze::{
+    Maze,
+    analysis::{MazeStats, analyze},
+    placement::Placement,
+};
+use rand::Rng;
+
+/// The most mazes `seed_constrained` will generate looking for one that fits.
+pub const MAX_ATTEMPTS: usize = 200;
+/// How many rooms' worth of mazes `seed_constrained` will generate, so big
+/// mazes get fewer attempts than small ones and giving up never takes long.
+/// It's counted in rooms rather than time so that a seed code makes the
+/// same maze however fast the machine is.
+pub const ROOM_BUDGET: usize = 20_000;
+
+/// Requirements a generated maze has to meet.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Constraints {
+    pub min_solution_length: usize,
+    pub max_dead_end_ratio: f64,
+    /// Junctions the shortest path has to pass through.
+    pub min_decision_points: usize,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self {
+            min_solution_length: 0,
+            max_dead_end_ratio: 1.0,
+            min_decision_points: 0,
+        }
+    }
+}
+
+impl Constraints {
+    /// How far `stats` falls short of the constraints: 0 when they're all met,
+    /// infinite when the goal can't be reached at all.
+    pub fn shortfall(&self, stats: &MazeStats) -> f64 {
+        let (Some(len), Some(decisions)) = (stats.solution_length, stats.decision_points) else {
+            return f64::INFINITY;
+        };
+        let under = |actual: usize, wanted: usize| {
+            wanted.saturating_sub(actual) as f64 / wanted.max(1) as f64
+        };
+        under(len, self.min_solution_length)
+            + (stats.dead_end_ratio() - self.max_dead_end_ratio).max(0.0)
+            + under(decisions, self.min_decision_points)
+    }
+    pub fn accepts(&self, stats: &MazeStats) -> bool {
+        self.shortfall(stats) == 0.0
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Difficulty {
+    #[default]
+    Any,
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Any,
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+    ];
+
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|d| d == self).unwrap_or(0)
+    }
+    pub fn from_index(ix: usize) -> Option<Self> {
+        Self::ALL.get(ix).copied()
+    }
+    pub fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Any => "any",
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+    /// Constraints for a maze with `rooms` rooms. Solution lengths and decision
+    /// points scale with the width of a square of that many rooms, which is
+    /// about how far apart the ends of the longest path in a heavily braided
+    /// maze are, so every generator can be made to meet them.
+    pub fn constraints(&self, rooms: usize) -> Constraints {
+        let side = (rooms as f64).sqrt();
+        match self {
+            Difficulty::Any => Constraints::default(),
+            Difficulty::Easy => Constraints {
+                max_dead_end_ratio: 0.15,
+                ..Constraints::default()
+            },
+            Difficulty::Normal => Constraints {
+                min_solution_length: side as usize,
+                max_dead_end_ratio: 0.4,
+                min_decision_points: (side / 4.0) as usize,
+            },
+            Difficulty::Hard => Constraints {
+                min_solution_length: (side * 1.5) as usize,
+                max_dead_end_ratio: 1.0,
+                min_decision_points: (side / 2.0) as usize,
+            },
+        }
+    }
+}
+
+/// Seeds, braids and places the start and goal in `maze`, then nudges it
+/// towards `constraints` with `fit`, over and over until it meets them. It
+/// gives up after `MAX_ATTEMPTS`, or `ROOM_BUDGET` rooms' worth, keeping
+/// whichever attempt came closest.
+///
+/// Returns the stats of the maze that was kept and whether it met the constraints.
+pub fn seed_constrained(
+    maze: &mut Maze,
+    rng: &mut impl Rng,
+    generator: Generator,
+    braid_percent: u8,
+    placement: Placement,
+    constraints: &Constraints,
+) -> (MazeStats, bool) {
+    let template = maze.clone();
+    let attempts = (ROOM_BUDGET / template.mask.active_count().max(1)).clamp(1, MAX_ATTEMPTS);
+    let mut best: Option<(f64, Maze, MazeStats)> = None;
+    for _ in 0..attempts {
+        let mut attempt = template.clone();
+        generator.seed(&mut attempt, rng);
+        braid(&mut attempt, rng, braid_percent);
+        attempt.place(placement, rng);
+        fit(&mut attempt, rng, placement, constraints);
+        let stats = analyze(&attempt);
+        let shortfall = constraints.shortfall(&stats);
+        if shortfall == 0.0 {
+            *maze = attempt;
+            return (stats, true);
+        }
+        if best.as_ref().is_none_or(|(s, _, _)| shortfall < *s) {
+            best = Some((shortfall, attempt, stats));
+        }
+    }
+    let (_, attempt, stats) = best.expect("there's always at least one attempt");
+    *maze = attempt;
+    (stats, false)
+}
+
+/// Changes `maze` to get closer to `constraints`: braids away dead ends until
+/// few enough are left, then moves whichever of the start and goal
+/// `placement` leaves free further apart until the solution is long enough.
+/// Braiding can shorten the solution, so it's done first. Does nothing to a
+/// maze that already fits.
+pub fn fit(maze: &mut Maze, rng: &mut impl Rng, placement: Placement, constraints: &Constraints) {
+    let rooms = maze.mask.active_count();
+    let max_dead_ends = (constraints.max_dead_end_ratio * rooms as f64) as usize;
+    braid_down_to(maze, rng, max_dead_ends);
+    maze.stretch(placement, constraints.min_solution_length);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::grid::Ix;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn test_seed_constrained() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let constraints = Difficulty::Hard.constraints(49);
+        for _ in 0..10 {
+            let mut m = Maze::new(7, 7);
+            let (stats, met) = seed_constrained(
+                &mut m,
+                &mut rng,
+                Generator::Backtrack,
+                0,
+                Placement::Corners,
+                &constraints,
+            );
+            assert!(met);
+            assert!(constraints.accepts(&stats));
+            assert_eq!(stats, analyze(&m));
+        }
+    }
+
+    #[test]
+    fn test_every_generator_fits() {
+        for generator in Generator::ALL {
+            for difficulty in Difficulty::ALL {
+                let constraints = difficulty.constraints(15 * 15);
+                let mut m = Maze::new(15, 15);
+                let (_, met) = seed_constrained(
+                    &mut m,
+                    &mut StdRng::seed_from_u64(3),
+                    generator,
+                    0,
+                    Placement::Corners,
+                    &constraints,
+                );
+                assert!(met, "{} {}", generator.name(), difficulty.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_placement_holds() {
+        let constraints = Difficulty::Hard.constraints(49);
+        let template = Maze::new(7, 7);
+        let size = template.size();
+        let centre = size.ix(3, 3).unwrap();
+        let on_border = |ix: Ix| [ix.y(), ix.x()].iter().any(|&n| n == 0 || n == 6);
+        for seed in 0..50 {
+            for placement in Placement::ALL {
+                let mut m = template.clone();
+                seed_constrained(
+                    &mut m,
+                    &mut StdRng::seed_from_u64(seed),
+                    Generator::Kruskal,
+                    100,
+                    placement,
+                    &constraints,
+                );
+                let ends = (m.current_ix, m.goal);
+                match placement {
+                    Placement::Corners => {
+                        assert_eq!((template.current_ix, template.goal), ends)
+                    }
+                    Placement::FarthestFromStart => assert_eq!(template.current_ix, ends.0),
+                    Placement::CentreToBorder => {
+                        assert_eq!(centre, ends.0, "seed {seed}");
+                        assert!(on_border(ends.1), "seed {seed}: {ends:?}");
+                    }
+                    Placement::Random | Placement::FarthestPair => (),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fit() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let mut m = Maze::new(10, 10);
+        Generator::Kruskal.seed(&mut m, &mut rng);
+        m.place(Placement::Random, &mut rng);
+        let constraints = Constraints {
+            min_solution_length: 15,
+            max_dead_end_ratio: 0.1,
+            min_decision_points: 0,
+        };
+        assert!(!constraints.accepts(&analyze(&m)));
+        fit(&mut m, &mut rng, Placement::Random, &constraints);
+        let stats = analyze(&m);
+        assert!(stats.dead_ends <= 10, "{stats:?}");
+        assert!(stats.solution_length >= Some(15), "{stats:?}");
+    }
+
+    #[test]
+    fn test_seed_constrained_gives_up() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let impossible = Constraints {
+            min_solution_length: 100,
+            ..Constraints::default()
+        };
+        let mut m = Maze::new(3, 3);
+        let (stats, met) = seed_constrained(
+            &mut m,
+            &mut rng,
+            Generator::Kruskal,
+            0,
+            Placement::FarthestPair,
+            &impossible,
+        );
+        assert!(!met);
+        assert!(stats.solution_length.is_some());
+    }
+}
@@ -1,13 +1,11 @@
-use super::Outcome;
+use super::{Outcome, board::Board};
 use crate::{
-    Direction,
-    maze::Maze,
+    maze::{MoveError, MoveResult},
     movement::MazeEvent,
-    ui::{self, RoomView, UnseenRoomView},
+    ui,
 };
 use color_eyre::Result;
 use crossterm::event;
-use multid::{BoundedIx2, iterators::V2Indices};
 use ratatui::{
     DefaultTerminal, Frame,
     buffer::Buffer,
@@ -16,11 +14,11 @@ use ratatui::{
 };
 use std::{collections::BTreeSet, marker::PhantomData};
 
-pub struct HiddenGame<'a, const N_ROWS: usize, const N_COLS: usize> {
-    _marker: PhantomData<&'a mut Maze<N_ROWS, N_COLS>>,
+pub struct HiddenGame<'a, B> {
+    _marker: PhantomData<&'a mut B>,
 }
 
-impl<'a, const N_ROWS: usize, const N_COLS: usize> HiddenGame<'a, N_ROWS, N_COLS> {
+impl<'a, B> HiddenGame<'a, B> {
     fn new() -> Self {
         Self {
             _marker: PhantomData,
@@ -28,84 +26,55 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> HiddenGame<'a, N_ROWS, N_COLS
     }
 }
 
-pub struct HiddenGameState<'a, const N_ROWS: usize, const N_COLS: usize> {
-    maze: &'a mut Maze<N_ROWS, N_COLS>,
-    seen: BTreeSet<BoundedIx2<N_ROWS, N_COLS>>,
+pub struct HiddenGameState<'a, B: Board> {
+    maze: &'a mut B,
+    seen: BTreeSet<B::Pos>,
 }
 
-impl<'a, const N_ROWS: usize, const N_COLS: usize> HiddenGameState<'a, N_ROWS, N_COLS> {
-    fn move_north(&mut self) {
-        self.maze.move_north();
-    }
-    fn move_east(&mut self) {
-        self.maze.move_east();
-    }
-    fn move_south(&mut self) {
-        self.maze.move_south();
-    }
-    fn move_west(&mut self) {
-        self.maze.move_west();
+impl<'a, B: Board> HiddenGameState<'a, B> {
+    fn step(&mut self, ev: &MazeEvent) -> Option<MoveResult> {
+        self.maze.step(ev)
     }
     fn insert_current_ix(&mut self) {
-        self.seen.insert(self.maze.current_ix);
+        self.seen.insert(self.maze.current());
     }
     fn is_done(&self) -> bool {
         self.maze.is_done()
     }
-    fn is_seen(&self, ix: &BoundedIx2<N_ROWS, N_COLS>) -> bool {
+    fn is_seen(&self, ix: &B::Pos) -> bool {
         self.seen.contains(ix)
     }
 }
 
-impl<'a, const N_ROWS: usize, const N_COLS: usize> StatefulWidget
-    for HiddenGame<'a, N_ROWS, N_COLS>
-{
-    type State = HiddenGameState<'a, N_ROWS, N_COLS>;
+impl<'a, B: Board> StatefulWidget for HiddenGame<'a, B> {
+    type State = HiddenGameState<'a, B>;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let (x_bounds, y_bounds) = state.maze.bounds();
         let c = Canvas::default()
-            .x_bounds([ui::MIN_X, ui::MAX_X])
-            .y_bounds([ui::MIN_Y, ui::MAX_Y])
+            .x_bounds(x_bounds)
+            .y_bounds(y_bounds)
             .background_color(ui::BG_COLOR)
             .paint(move |ctx| {
-                for ix in V2Indices::<N_ROWS, N_COLS>::new() {
-                    let x = -200.0 + ui::ROOM_SIZE * ix.x() as f64;
-                    let y = 200.0 - ui::ROOM_SIZE * ix.y() as f64;
-                    let label_x = -200.0 + (ui::ROOM_SIZE * ix.x() as f64) + ui::SEG_LEN * 3.5;
-                    let label_y = 200.0 - (ui::ROOM_SIZE * ix.y() as f64 + ui::SEG_LEN * 3.5);
-                    if ix == state.maze.goal {
-                        ctx.print(label_x, label_y, "\u{1f945}")
-                    };
+                for ix in state.maze.rooms() {
                     if state.is_seen(&ix) {
-                        let room = &state.maze.rooms[ix];
-                        let view = RoomView { x, y, room };
-                        ctx.draw(&view);
-                        if ix == state.maze.current_ix && ix == state.maze.goal {
+                        state.maze.draw_room(ctx, ix);
+                    } else {
+                        state.maze.draw_unseen(ctx, ix, &|i| state.is_seen(&i));
+                    }
+                    // the goal is shown even before its room has been seen
+                    for (label_x, label_y) in state.maze.labels_at(ix) {
+                        if ix == state.maze.current() && ix == state.maze.goal() {
                             ctx.print(label_x, label_y, "\u{1f940}")
-                        } else if ix == state.maze.current_ix {
+                        } else if ix == state.maze.current() {
                             ctx.print(label_x, label_y, "\u{1f600}")
-                        } else if ix == state.maze.goal {
+                        } else if ix == state.maze.goal() {
                             ctx.print(label_x, label_y, "\u{1f945}")
+                        } else if state.is_seen(&ix)
+                            && let Some(item) = state.maze.item_at(ix)
+                        {
+                            ctx.print(label_x, label_y, item)
                         }
-                    } else {
-                        let mut unseen: Vec<Direction> = Vec::with_capacity(4);
-                        if ix.north().map(|i| !state.seen.contains(&i)).unwrap_or(true) {
-                            unseen.push(Direction::North);
-                        }
-                        if ix.south().map(|i| !state.seen.contains(&i)).unwrap_or(true) {
-                            unseen.push(Direction::South);
-                        }
-                        if ix.east().map(|i| !state.seen.contains(&i)).unwrap_or(true) {
-                            unseen.push(Direction::East);
-                        }
-                        if ix.west().map(|i| !state.seen.contains(&i)).unwrap_or(true) {
-                            unseen.push(Direction::West);
-                        }
-                        ctx.draw(&UnseenRoomView {
-                            x,
-                            y,
-                            hidden_walls: unseen,
-                        });
                     }
                 }
             });
@@ -113,29 +82,30 @@ impl<'a, const N_ROWS: usize, const N_COLS: usize> StatefulWidget
     }
 }
 
-pub fn game<const N_ROWS: usize, const N_COLS: usize>(
-    terminal: &mut DefaultTerminal,
-    maze: &mut Maze<N_ROWS, N_COLS>,
-) -> Result<Outcome> {
-    let mut st: HiddenGameState<N_ROWS, N_COLS> = HiddenGameState {
+pub fn game<B: Board>(terminal: &mut DefaultTerminal, maze: &mut B, info: &str) -> Result<Outcome> {
+    let mut st: HiddenGameState<B> = HiddenGameState {
         maze,
         seen: BTreeSet::new(),
     };
+    let mut blocked: Option<MoveError> = None;
     loop {
         st.insert_current_ix();
+        let status = ui::status(&st.maze.info(info), blocked);
         terminal.draw(|frame: &mut Frame| {
-            frame.render_stateful_widget(HiddenGame::new(), frame.area(), &mut st)
+            let [maze_area, footer_area] = ui::footer_layout(frame.area());
+            frame.render_stateful_widget(HiddenGame::new(), maze_area, &mut st);
+            frame.render_widget(ui::footer(&status), footer_area);
         })?;
         if st.is_done() {
             return Ok(Outcome::Win);
         }
         match event::read()?.into() {
-            MazeEvent::MoveN => &st.move_north(),
-            MazeEvent::MoveS => &st.move_south(),
-            MazeEvent::MoveE => &st.move_east(),
-            MazeEvent::MoveW => &st.move_west(),
             MazeEvent::Quit => return Ok(Outcome::Quit),
-            _ => &(),
+            ev => {
+                if let Some(result) = st.step(&ev) {
+                    blocked = result.err();
+                }
+            }
         };
     }
 }
@@ -1,4 +1,10 @@
-use super::{Game, Outcome};
+use super::{Difficulty, Game, Generator, Outcome, SeedCode, Seeder, Shape};
+use crate::{
+    grid::Size,
+    maze::{mask::Mask, placement::Placement, wrap::Wrap},
+};
+use crossterm::event::KeyCode;
+use rand::Rng;
 use ratatui::{
     buffer::Buffer,
     layout::{Alignment, Constraint, Layout, Rect},
@@ -9,12 +15,37 @@ use ratatui::{
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum MenuChoice {
     Quit,
+    Seed,
+    Size,
+    Shape,
+    Wrap,
+    Braid,
+    OneWay,
+    Difficulty,
+    Placement,
     Game(Game),
 }
 
 impl MenuChoice {
-    fn to_list<'a>() -> List<'a> {
-        List::new(["Basic", "Hidden", "Lantern", "Quit"])
+    fn to_list<'a>(state: &MenuState) -> List<'a> {
+        List::new([
+            String::from("Basic"),
+            String::from("Hidden"),
+            String::from("Lantern"),
+            String::from("Keys"),
+            String::from("Seed"),
+            match state.mask {
+                Some(_) => format!("Size: {} (mask)", state.size),
+                None => format!("Size: {}", state.size),
+            },
+            format!("Shape: {}", state.shape.name()),
+            format!("Wrap: {}", state.wrap.name()),
+            format!("Braid: {}%", state.braid),
+            format!("One-way: {}%", state.one_way),
+            format!("Difficulty: {}", state.difficulty.name()),
+            format!("Start: {}", state.placement.name()),
+            String::from("Quit"),
+        ])
     }
 }
 
@@ -24,6 +55,15 @@ impl From<usize> for MenuChoice {
             0 => MenuChoice::Game(Game::Basic),
             1 => MenuChoice::Game(Game::Hidden),
             2 => MenuChoice::Game(Game::Lantern),
+            3 => MenuChoice::Game(Game::Keys),
+            4 => MenuChoice::Seed,
+            5 => MenuChoice::Size,
+            6 => MenuChoice::Shape,
+            7 => MenuChoice::Wrap,
+            8 => MenuChoice::Braid,
+            9 => MenuChoice::OneWay,
+            10 => MenuChoice::Difficulty,
+            11 => MenuChoice::Placement,
             _ => MenuChoice::Quit,
         }
     }
@@ -31,17 +71,44 @@ impl From<usize> for MenuChoice {
 
 pub struct GameMenu;
 
+/// The square maze sizes `Size` steps through.
+pub const SIZES: [usize; 9] = [5, 7, 10, 15, 20, 30, 50, 75, 100];
+/// The most rows or columns a maze can have without a mask.
+pub const MAX_SIZE: usize = SIZES[SIZES.len() - 1];
+
 #[derive(Debug)]
 pub struct MenuState {
     list: ListState,
     pub choice: Option<MenuChoice>,
-    prev_outcome: Option<Outcome>,
+    pub generator: Generator,
+    pub size: Size,
+    /// Shapes every maze, and fixes the size to its own.
+    pub mask: Option<Mask>,
+    /// Masks only shape square mazes, so with one this stays square.
+    pub shape: Shape,
+    /// Only square rooms, alone or in floors, wrap.
+    pub wrap: Wrap,
+    pub braid: u8,
+    pub one_way: u8,
+    pub difficulty: Difficulty,
+    pub placement: Placement,
+    prev_outcome: Option<(Outcome, SeedCode)>,
+    seed_entry: Option<String>,
+    next_seed: Option<u64>,
+    seed_error: Option<String>,
 }
 
 impl MenuState {
-    pub fn game_over(&mut self, outcome: Outcome) {
+    pub fn with_mask(mask: Mask) -> Self {
+        Self {
+            size: mask.size(),
+            mask: Some(mask),
+            ..Self::default()
+        }
+    }
+    pub fn game_over(&mut self, outcome: Outcome, code: SeedCode) {
         self.choice = None;
-        self.prev_outcome = Some(outcome);
+        self.prev_outcome = Some((outcome, code));
         self.list.select_first();
     }
     pub fn unchoose(&mut self) {
@@ -59,11 +126,163 @@ impl MenuState {
     pub fn select_quit(&mut self) {
         self.list.select_last();
     }
-    pub fn outcome_msg(&self) -> &str {
+    pub fn next_generator(&mut self) {
+        self.generator = self.generator.next();
+    }
+    pub fn previous_generator(&mut self) {
+        self.generator = self.generator.previous();
+    }
+    /// The seed for the next maze: the one entered by the player if there is
+    /// one, otherwise a fresh random one.
+    pub fn next_seed_code(&mut self, rng: &mut impl Rng) -> SeedCode {
+        SeedCode {
+            seed: self.next_seed.take().unwrap_or_else(|| rng.random()),
+            n_rows: self.size.n_rows,
+            n_cols: self.size.n_cols,
+            generator: self.generator,
+            braid: self.braid,
+            one_way: self.one_way,
+            difficulty: self.difficulty,
+            placement: self.placement,
+            wrap: self.wrap,
+            shape: self.shape,
+        }
+    }
+    /// The mask for the next maze: the one the game was started with, or
+    /// every room of the chosen size.
+    pub fn mask(&self) -> Mask {
+        self.mask.clone().unwrap_or_else(|| Mask::full(self.size))
+    }
+    /// Steps up to the next of `SIZES`, wrapping back around to the smallest.
+    /// A mask's size can't be changed.
+    pub fn cycle_size(&mut self) {
+        if self.mask.is_some() {
+            return;
+        }
+        let current = self.size.n_rows.max(self.size.n_cols);
+        let next = SIZES.into_iter().find(|&n| n > current).unwrap_or(SIZES[0]);
+        self.size = Size::new(next, next);
+    }
+    pub fn cycle_shape(&mut self) {
+        if self.mask.is_none() {
+            self.shape = self.shape.next();
+        }
+    }
+    pub fn cycle_wrap(&mut self) {
+        self.wrap = self.wrap.next();
+    }
+    /// Steps the braid factor up by a quarter, wrapping back around to none.
+    pub fn cycle_braid(&mut self) {
+        self.braid = if self.braid >= 100 {
+            0
+        } else {
+            (self.braid / 25 + 1) * 25
+        };
+    }
+    /// Steps the share of one-way doors up as far as a seed code can hold,
+    /// wrapping back around to none.
+    pub fn cycle_one_way(&mut self) {
+        self.one_way = if self.one_way >= SeedCode::MAX_ONE_WAY {
+            0
+        } else {
+            self.one_way + SeedCode::ONE_WAY_STEP
+        };
+    }
+    pub fn cycle_difficulty(&mut self) {
+        self.difficulty = self.difficulty.next();
+    }
+    pub fn cycle_placement(&mut self) {
+        self.placement = self.placement.next();
+    }
+    pub fn start_seed_entry(&mut self) {
+        self.seed_entry = Some(String::new());
+        self.seed_error = None;
+    }
+    pub fn is_entering_seed(&self) -> bool {
+        self.seed_entry.is_some()
+    }
+    /// Handles a key press while a seed is being typed. A plain number is used as
+    /// the seed for the current generator; anything else is read as a seed code,
+    /// which also picks the generator and, unless there's a mask, the size.
+    pub fn seed_entry_key(&mut self, key: KeyCode) {
+        let Some(entry) = self.seed_entry.as_mut() else {
+            return;
+        };
+        match key {
+            KeyCode::Char(c) if c.is_ascii_alphanumeric() || c == '-' => entry.push(c),
+            KeyCode::Backspace => {
+                entry.pop();
+            }
+            KeyCode::Esc => self.seed_entry = None,
+            KeyCode::Enter => {
+                let entry = self.seed_entry.take().unwrap_or_default();
+                if entry.is_empty() {
+                    self.next_seed = None;
+                } else if let Ok(seed) = entry.parse::<u64>() {
+                    self.next_seed = Some(seed);
+                } else {
+                    match SeedCode::decode(&entry) {
+                        Err(e) => self.seed_error = Some(format!("{entry} {e}")),
+                        Ok(code)
+                            if self.mask.is_some()
+                                && (code.n_rows, code.n_cols)
+                                    != (self.size.n_rows, self.size.n_cols) =>
+                        {
+                            self.seed_error = Some(format!(
+                                "that code is for a {}x{} maze",
+                                code.n_rows, code.n_cols
+                            ))
+                        }
+                        // a code has room for far bigger mazes than the menu
+                        // offers, which would take too long to make
+                        Ok(code)
+                            if self.mask.is_none() && code.n_rows.max(code.n_cols) > MAX_SIZE =>
+                        {
+                            self.seed_error = Some(format!(
+                                "that code is for a {}x{} maze, bigger than {MAX_SIZE}x{MAX_SIZE}",
+                                code.n_rows, code.n_cols
+                            ))
+                        }
+                        Ok(code) => {
+                            self.size = Size::new(code.n_rows, code.n_cols);
+                            self.generator = code.generator;
+                            self.braid = code.braid;
+                            self.one_way = code.one_way;
+                            self.difficulty = code.difficulty;
+                            self.placement = code.placement;
+                            self.wrap = code.wrap;
+                            self.shape = code.shape;
+                            self.next_seed = Some(code.seed);
+                        }
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    pub fn seed_msg(&self) -> String {
+        if let Some(entry) = &self.seed_entry {
+            format!("{entry}_\nenter to confirm, esc to cancel")
+        } else if let Some(err) = &self.seed_error {
+            err.clone()
+        } else if let Some(seed) = self.next_seed {
+            format!("next maze: seed {seed}")
+        } else {
+            String::from("random")
+        }
+    }
+    pub fn generator_msg(&self) -> String {
+        format!(
+            "< {} >\n{}",
+            self.generator.name(),
+            self.generator.description()
+        )
+    }
+    pub fn outcome_msg(&self) -> String {
         match self.prev_outcome {
-            None => "",
-            Some(Outcome::Win) => "you won!",
-            Some(Outcome::Quit) => "you quit",
+            None => String::new(),
+            Some((Outcome::Win, code)) => format!("you won!\n{code}"),
+            Some((Outcome::Quit, code)) => format!("you quit\n{code}"),
         }
     }
     fn list_state_mut(&mut self) -> &mut ListState {
@@ -76,7 +295,19 @@ impl Default for MenuState {
         let mut this = MenuState {
             list: ListState::default(),
             choice: None,
+            generator: Generator::default(),
+            size: Size::new(7, 7),
+            mask: None,
+            shape: Shape::default(),
+            wrap: Wrap::default(),
+            braid: 0,
+            one_way: 0,
+            difficulty: Difficulty::default(),
+            placement: Placement::default(),
             prev_outcome: None,
+            seed_entry: None,
+            next_seed: None,
+            seed_error: None,
         };
         this.list.select_first();
         this
@@ -93,15 +324,36 @@ impl StatefulWidget for GameMenu {
             .fg(Color::Green)
             .padding(Padding::symmetric(5, 1));
         let inner_area = b.inner(area);
-        let vertical = Layout::vertical([Constraint::Min(0), Constraint::Length(5)]);
-        let [menu_area, outcome_area] = vertical.areas(inner_area);
-        let l = MenuChoice::to_list()
+        let vertical = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(4),
+            Constraint::Length(4),
+            Constraint::Length(5),
+        ]);
+        let [menu_area, generator_area, seed_area, outcome_area] = vertical.areas(inner_area);
+        let l = MenuChoice::to_list(state)
             .block(Block::bordered())
             .fg(Color::Green)
             .highlight_style(Style::new().reversed())
             .highlight_symbol("*");
         Widget::render(b, area, buf);
         StatefulWidget::render(l, menu_area, buf, state.list_state_mut());
+        Widget::render(
+            Paragraph::new(state.generator_msg())
+                .alignment(Alignment::Center)
+                .block(Block::bordered().title("Generator"))
+                .fg(Color::Green),
+            generator_area,
+            buf,
+        );
+        Widget::render(
+            Paragraph::new(state.seed_msg())
+                .alignment(Alignment::Center)
+                .block(Block::bordered().title("Seed"))
+                .fg(Color::Green),
+            seed_area,
+            buf,
+        );
         Widget::render(
             Paragraph::new(state.outcome_msg())
                 .alignment(Alignment::Center)
@@ -1,23 +1,79 @@
-use crate::{maze::Maze, movement::MazeEvent};
+use crate::{
+    grid::Size,
+    maze::{
+        Maze, floors::Floors, hex::HexMaze, keys::lock_doors, mask::Mask, polar::PolarMaze,
+        wrap::Wrap,
+    },
+    movement::MazeEvent,
+};
+use board::Board;
 use color_eyre::Result;
-use crossterm::event;
-use rand::rngs::ThreadRng;
-use ratatui::Frame;
+use crossterm::event::{self, Event, KeyEvent};
+use rand::{
+    SeedableRng,
+    rngs::{StdRng, ThreadRng},
+};
+use ratatui::{DefaultTerminal, Frame};
 
 pub mod basic;
+pub mod board;
+pub mod difficulty;
 pub mod hidden;
 pub mod lantern;
 pub mod menu;
+pub mod seed;
 pub mod seeders;
 
+pub use difficulty::{Constraints, Difficulty, seed_constrained};
 use menu::{MenuChoice, MenuState};
-pub use seeders::{seed_doors_naive, seed_doors_path};
+pub use seed::SeedCode;
+pub use seeders::{
+    CellSelection, EllerRows, Generator, Seeder, braid, braid_down_to, one_way, seed_backtrack,
+    seed_doors_aldous_broder, seed_doors_backtrack, seed_doors_binary_tree, seed_doors_division,
+    seed_doors_eller, seed_doors_growing_tree, seed_doors_hunt_and_kill, seed_doors_kruskal,
+    seed_doors_naive, seed_doors_path, seed_doors_prim, seed_doors_sidewinder, seed_doors_wilson,
+    seed_stairs,
+};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum Game {
     Basic,
     Hidden,
     Lantern,
+    Keys,
+}
+
+/// The shape of the rooms a maze is made of.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Shape {
+    #[default]
+    Square,
+    Hex,
+    Polar,
+    /// Square mazes stacked on top of each other.
+    Floors,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 4] = [Shape::Square, Shape::Hex, Shape::Polar, Shape::Floors];
+
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|s| s == self).unwrap_or(0)
+    }
+    pub fn from_index(ix: usize) -> Option<Self> {
+        Self::ALL.get(ix).copied()
+    }
+    pub fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Square => "square",
+            Shape::Hex => "hex",
+            Shape::Polar => "polar",
+            Shape::Floors => "floors",
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -26,10 +82,12 @@ pub enum Outcome {
     Quit,
 }
 
-pub fn game_loop<const N_ROWS: usize, const N_COLS: usize>() -> Result<()> {
+/// Runs the menu until the player quits. If there's a `mask`, every maze is
+/// shaped by it; otherwise the player picks the size.
+pub fn game_loop(mask: Option<Mask>) -> Result<()> {
     let mut terminal = ratatui::init();
     let mut rng = ThreadRng::default();
-    let mut menu_state = MenuState::default();
+    let mut menu_state = mask.map(MenuState::with_mask).unwrap_or_default();
     loop {
         terminal.draw(|frame: &mut Frame| {
             frame.render_stateful_widget(menu::GameMenu, frame.area(), &mut menu_state)
@@ -37,29 +95,70 @@ pub fn game_loop<const N_ROWS: usize, const N_COLS: usize>() -> Result<()> {
         match menu_state.choice {
             None => (),
             Some(MenuChoice::Quit) => break,
-            Some(MenuChoice::Game(Game::Basic)) => {
-                let mut maze = new_seeded::<N_ROWS, N_COLS>(&mut rng);
-                let outcome = basic::game(&mut terminal, &mut maze)?;
-                menu_state.game_over(outcome);
-                continue;
-            }
-            Some(MenuChoice::Game(Game::Hidden)) => {
-                let mut maze = new_seeded::<N_ROWS, N_COLS>(&mut rng);
-                let outcome = hidden::game(&mut terminal, &mut maze)?;
-                menu_state.game_over(outcome);
-                continue;
-            }
-            Some(MenuChoice::Game(Game::Lantern)) => {
-                let mut maze = new_seeded::<N_ROWS, N_COLS>(&mut rng);
-                let outcome = lantern::game(&mut terminal, &mut maze)?;
-                menu_state.game_over(outcome);
+            Some(MenuChoice::Seed) => menu_state.start_seed_entry(),
+            Some(MenuChoice::Size) => menu_state.cycle_size(),
+            Some(MenuChoice::Shape) => menu_state.cycle_shape(),
+            Some(MenuChoice::Wrap) => menu_state.cycle_wrap(),
+            Some(MenuChoice::Braid) => menu_state.cycle_braid(),
+            Some(MenuChoice::OneWay) => menu_state.cycle_one_way(),
+            Some(MenuChoice::Difficulty) => menu_state.cycle_difficulty(),
+            Some(MenuChoice::Placement) => menu_state.cycle_placement(),
+            Some(MenuChoice::Game(game)) => {
+                let code = menu_state.next_seed_code(&mut rng);
+                let outcome = match (game, code.shape) {
+                    // keys are only hidden in square mazes, so that's what
+                    // the keys game is played on whatever the shape
+                    (Game::Keys, shape) => {
+                        let (mut maze, met) = new_seeded(&code, menu_state.mask());
+                        let mut rng = StdRng::seed_from_u64(code.seed);
+                        lock_doors(&mut maze, &mut rng, KEY_COUNT);
+                        let info = match shape {
+                            Shape::Square => info(&code, met),
+                            shape => format!(
+                                "{} / keys need square, not {}",
+                                info(&code, met),
+                                shape.name()
+                            ),
+                        };
+                        play(game, &mut terminal, &mut maze, &info)?
+                    }
+                    (game, Shape::Square) => {
+                        let (mut maze, met) = new_seeded(&code, menu_state.mask());
+                        play(game, &mut terminal, &mut maze, &info(&code, met))?
+                    }
+                    (game, Shape::Hex) => {
+                        let mut maze = new_hex(&code);
+                        let info = format!("{code} / hex");
+                        play(game, &mut terminal, &mut maze, &info)?
+                    }
+                    (game, Shape::Polar) => {
+                        let mut maze = new_polar(&code);
+                        let info = format!("{code} / polar");
+                        play(game, &mut terminal, &mut maze, &info)?
+                    }
+                    (game, Shape::Floors) => {
+                        let (mut maze, met) = new_floors(&code, menu_state.mask());
+                        let info = format!("{} / floors", info(&code, met));
+                        play(game, &mut terminal, &mut maze, &info)?
+                    }
+                };
+                menu_state.game_over(outcome, code);
                 continue;
             }
         };
         menu_state.unchoose();
-        match event::read()?.into() {
+        let ev = event::read()?;
+        if menu_state.is_entering_seed() {
+            if let Event::Key(KeyEvent { code, .. }) = ev {
+                menu_state.seed_entry_key(code);
+            }
+            continue;
+        }
+        match ev.into() {
             MazeEvent::MoveN => &menu_state.select_previous(),
             MazeEvent::MoveS => &menu_state.select_next(),
+            MazeEvent::MoveE => &menu_state.next_generator(),
+            MazeEvent::MoveW => &menu_state.previous_generator(),
             MazeEvent::Quit => &menu_state.select_quit(),
             MazeEvent::Enter => &menu_state.choose(),
             _ => &(),
@@ -69,10 +168,95 @@ pub fn game_loop<const N_ROWS: usize, const N_COLS: usize>() -> Result<()> {
     Ok(())
 }
 
-fn new_seeded<const N_ROWS: usize, const N_COLS: usize>(
-    rng: &mut ThreadRng,
-) -> Maze<N_ROWS, N_COLS> {
-    let mut maze = Maze::<N_ROWS, N_COLS>::default();
-    seed_doors_path(&mut maze, rng);
+/// The footer info for a square maze made from `code`, with its wrap, owning
+/// up if it couldn't be made to fit its difficulty.
+fn info(code: &SeedCode, met: bool) -> String {
+    let mut info = code.to_string();
+    if code.wrap != Wrap::None {
+        info += &format!(" / {}", code.wrap.name());
+    }
+    if !met {
+        info += &format!(" / not quite {}", code.difficulty.name());
+    }
+    info
+}
+
+/// Plays one of the modes that work on any shape of maze.
+fn play<B: Board>(
+    game: Game,
+    terminal: &mut DefaultTerminal,
+    maze: &mut B,
+    info: &str,
+) -> Result<Outcome> {
+    match game {
+        Game::Hidden => hidden::game(terminal, maze, info),
+        Game::Lantern => lantern::game(terminal, maze, info),
+        Game::Basic | Game::Keys => basic::game(terminal, maze, info),
+    }
+}
+
+/// Hex mazes are always made by the backtracker, so only the seed and size of
+/// `code` are used.
+fn new_hex(code: &SeedCode) -> HexMaze {
+    let mut maze = HexMaze::new(Size::new(code.n_rows, code.n_cols));
+    seed_backtrack(&mut maze, &mut StdRng::seed_from_u64(code.seed));
     maze
 }
+
+/// Polar mazes have a ring for each row of `code`'s size, and like hex mazes
+/// are always made by the backtracker.
+fn new_polar(code: &SeedCode) -> PolarMaze {
+    let mut maze = PolarMaze::new(code.n_rows);
+    seed_backtrack(&mut maze, &mut StdRng::seed_from_u64(code.seed));
+    maze
+}
+
+/// How many doors the keys game tries to lock.
+const KEY_COUNT: usize = 3;
+
+/// How many floors a maze with floors has.
+const FLOOR_COUNT: usize = 3;
+/// How many flights of stairs join each floor to the next.
+const STAIRS_PER_FLOOR: usize = 2;
+
+/// Each floor is made like a square maze from `code`, with the seed counting up
+/// a floor at a time, then they're joined by stairs. One-way doors are left
+/// out, as they could cut the stairs off from the start. Also says whether
+/// every floor fits the difficulty.
+fn new_floors(code: &SeedCode, mask: Mask) -> (Floors, bool) {
+    let (floors, met): (Vec<Maze>, Vec<bool>) = (0..FLOOR_COUNT as u64)
+        .map(|floor| {
+            let code = SeedCode {
+                seed: code.seed.wrapping_add(floor),
+                one_way: 0,
+                ..*code
+            };
+            new_seeded(&code, mask.clone())
+        })
+        .unzip();
+    let mut maze = Floors::new(floors);
+    seed_stairs(
+        &mut maze,
+        &mut StdRng::seed_from_u64(code.seed),
+        STAIRS_PER_FLOOR,
+    );
+    (maze, met.into_iter().all(|met| met))
+}
+
+/// A square maze made from `code`, and whether it fits the difficulty.
+fn new_seeded(code: &SeedCode, mask: Mask) -> (Maze, bool) {
+    let constraints = code.difficulty.constraints(mask.active_count());
+    let mut maze = Maze::with_mask(mask);
+    maze.wrap = code.wrap;
+    let mut rng = StdRng::seed_from_u64(code.seed);
+    let (_, met) = seed_constrained(
+        &mut maze,
+        &mut rng,
+        code.generator,
+        code.braid,
+        code.placement,
+        &constraints,
+    );
+    one_way(&mut maze, &mut rng, code.one_way);
+    (maze, met)
+}
@@ -0,0 +1,309 @@
+use super::{Difficulty, Generator, Shape};
+use crate::maze::{placement::Placement, wrap::Wrap};
+use std::fmt;
+
+/// Crockford's base32 alphabet: no I, L, O or U, so codes are hard to misread.
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const CODE_LEN: usize = 24;
+/// Bumped whenever the fields of a code change, so a code from another
+/// version is refused rather than read as a different maze.
+const VERSION: u128 = 1;
+const VERSION_BITS: u32 = 5;
+const SPARE_BITS: u32 = 7;
+/// Codes from before they had a version: one-way doors and placement, then
+/// just the seed, size and generator. Codes from the 20 character versions
+/// in between can't be told apart, so those are refused.
+const UNVERSIONED_LEN: usize = 21;
+const FIRST_LEN: usize = 18;
+const OLD_LENS: [usize; 1] = [20];
+const SIZE_BITS: u32 = 10;
+const GENERATOR_BITS: u32 = 6;
+const BRAID_BITS: u32 = 7;
+const DIFFICULTY_BITS: u32 = 2;
+const PLACEMENT_BITS: u32 = 3;
+const ONE_WAY_BITS: u32 = 2;
+/// Taken from what were spare bits, so codes from before them are for
+/// unwrapped square mazes.
+const WRAP_BITS: u32 = 2;
+const SHAPE_BITS: u32 = 2;
+
+/// Everything needed to regenerate a maze exactly: the RNG seed, the grid size,
+/// the generator, how much it was braided, the difficulty it was held to,
+/// where the start and goal went, how many doors were made one-way, which
+/// edges wrap and the shape of the rooms.
+///
+/// It packs into a 24 character base32 code (120 bits: 5 of version, 7 spare,
+/// 2 of shape, 2 of wrap, 2 of one-way, 64 of seed, 10 each of rows and columns, 6 of
+/// generator, 7 of braid, 2 of difficulty and 3 of placement) that's easy to
+/// read out and share. The version is the first character.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SeedCode {
+    pub seed: u64,
+    pub n_rows: usize,
+    pub n_cols: usize,
+    pub generator: Generator,
+    /// Percentage of dead ends removed after generating, see `seeders::braid`.
+    pub braid: u8,
+    pub difficulty: Difficulty,
+    pub placement: Placement,
+    /// Percentage of open doors made one-way, see `seeders::one_way`. Only
+    /// multiples of `ONE_WAY_STEP`, up to `MAX_ONE_WAY`, fit in a code.
+    pub one_way: u8,
+    pub wrap: Wrap,
+    pub shape: Shape,
+}
+
+impl SeedCode {
+    pub const MAX_SIZE: usize = (1 << SIZE_BITS) - 1;
+    pub const ONE_WAY_STEP: u8 = 10;
+    pub const MAX_ONE_WAY: u8 = ((1 << ONE_WAY_BITS) - 1) * Self::ONE_WAY_STEP;
+
+    pub fn encode(&self) -> String {
+        let mut packed: u128 = VERSION << SPARE_BITS;
+        packed = (packed << SHAPE_BITS) | self.shape.index() as u128;
+        packed = (packed << WRAP_BITS) | self.wrap.index() as u128;
+        packed = (packed << ONE_WAY_BITS)
+            | (self.one_way.min(Self::MAX_ONE_WAY) / Self::ONE_WAY_STEP) as u128;
+        packed = (packed << 64) | self.seed as u128;
+        packed = (packed << SIZE_BITS) | self.n_rows.min(Self::MAX_SIZE) as u128;
+        packed = (packed << SIZE_BITS) | self.n_cols.min(Self::MAX_SIZE) as u128;
+        packed = (packed << GENERATOR_BITS) | self.generator.index() as u128;
+        packed = (packed << BRAID_BITS) | self.braid.min(100) as u128;
+        packed = (packed << DIFFICULTY_BITS) | self.difficulty.index() as u128;
+        packed = (packed << PLACEMENT_BITS) | self.placement.index() as u128;
+        let chars: String = (0..CODE_LEN)
+            .rev()
+            .map(|i| ALPHABET[((packed >> (5 * i)) & 0x1f) as usize] as char)
+            .collect();
+        format!(
+            "{}-{}-{}-{}",
+            &chars[0..6],
+            &chars[6..12],
+            &chars[12..18],
+            &chars[18..24]
+        )
+    }
+
+    /// Parses a code made by `encode`, or by an older version where that can
+    /// be done unambiguously. Case and dashes are ignored, and I/L and O are
+    /// read as 1 and 0.
+    pub fn decode(code: &str) -> Result<Self, CodeError> {
+        let mut packed: u128 = 0;
+        let mut len = 0;
+        for c in code.chars().filter(|&c| c != '-') {
+            let c = match c.to_ascii_uppercase() {
+                'I' | 'L' => '1',
+                'O' => '0',
+                c => c,
+            };
+            let digit = ALPHABET
+                .iter()
+                .position(|&a| a as char == c)
+                .ok_or(CodeError::Invalid)?;
+            packed = (packed << 5) | digit as u128;
+            len += 1;
+        }
+        let mut take = |bits: u32| {
+            let field = packed & ((1u128 << bits) - 1);
+            packed >>= bits;
+            field
+        };
+        let (placement, difficulty, braid) = if len == FIRST_LEN {
+            (Placement::Corners.index(), Difficulty::Any.index(), 0)
+        } else {
+            (
+                take(PLACEMENT_BITS) as usize,
+                take(DIFFICULTY_BITS) as usize,
+                take(BRAID_BITS) as u8,
+            )
+        };
+        let generator = take(GENERATOR_BITS) as usize;
+        let n_cols = take(SIZE_BITS) as usize;
+        let n_rows = take(SIZE_BITS) as usize;
+        let seed = take(64) as u64;
+        let one_way = if len == FIRST_LEN {
+            0
+        } else {
+            take(ONE_WAY_BITS) as u8 * Self::ONE_WAY_STEP
+        };
+        let (wrap, shape) = match len {
+            CODE_LEN => {
+                let wrap = take(WRAP_BITS) as usize;
+                let shape = take(SHAPE_BITS) as usize;
+                let spare = take(SPARE_BITS);
+                match take(VERSION_BITS) {
+                    VERSION if spare == 0 => (wrap, shape),
+                    version if version > VERSION => return Err(CodeError::Newer),
+                    _ => return Err(CodeError::Invalid),
+                }
+            }
+            UNVERSIONED_LEN | FIRST_LEN => (Wrap::None.index(), Shape::Square.index()),
+            len if OLD_LENS.contains(&len) => return Err(CodeError::Old),
+            _ => return Err(CodeError::Invalid),
+        };
+        let (Some(placement), Some(difficulty), Some(generator), Some(wrap), Some(shape)) = (
+            Placement::from_index(placement),
+            Difficulty::from_index(difficulty),
+            Generator::from_index(generator),
+            Wrap::from_index(wrap),
+            Shape::from_index(shape),
+        ) else {
+            return Err(CodeError::Invalid);
+        };
+        if n_rows == 0 || n_cols == 0 || braid > 100 || packed != 0 {
+            return Err(CodeError::Invalid);
+        }
+        Ok(Self {
+            seed,
+            n_rows,
+            n_cols,
+            generator,
+            braid,
+            difficulty,
+            placement,
+            one_way,
+            wrap,
+            shape,
+        })
+    }
+}
+
+/// Why a seed code couldn't be read, from `SeedCode::decode`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CodeError {
+    /// It isn't a code, or it's been mistyped.
+    Invalid,
+    /// It's from a version too old to tell which maze it meant.
+    Old,
+    /// It's from a newer version than this one.
+    Newer,
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodeError::Invalid => write!(f, "isn't a valid seed"),
+            CodeError::Old => write!(f, "is from an older version and can't be replayed"),
+            CodeError::Newer => write!(f, "is from a newer version"),
+        }
+    }
+}
+
+impl std::error::Error for CodeError {}
+
+impl fmt::Display for SeedCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "seed {} / code {}", self.seed, self.encode())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for (i, generator) in Generator::ALL.into_iter().enumerate() {
+            let code = SeedCode {
+                seed: u64::MAX - i as u64 * 7919,
+                n_rows: 7 + i,
+                n_cols: SeedCode::MAX_SIZE - i,
+                generator,
+                braid: (i * 7) as u8,
+                difficulty: Difficulty::from_index(i % 4).unwrap(),
+                placement: Placement::from_index(i % 5).unwrap(),
+                one_way: (i % 4) as u8 * SeedCode::ONE_WAY_STEP,
+                wrap: Wrap::ALL[i % 4],
+                shape: Shape::ALL[i / 4 % 4],
+            };
+            assert_eq!(Ok(code), SeedCode::decode(&code.encode()));
+        }
+    }
+
+    #[test]
+    fn test_round_trip_shapes() {
+        let code = SeedCode::decode("100000-000000-000AG1-R1R000").unwrap();
+        for shape in Shape::ALL {
+            let code = SeedCode { shape, ..code };
+            assert_eq!(Ok(code), SeedCode::decode(&code.encode()), "{shape:?}");
+        }
+    }
+
+    #[test]
+    fn test_decode_lenient() {
+        let code = SeedCode {
+            seed: 1234,
+            n_rows: 7,
+            n_cols: 7,
+            generator: Generator::Kruskal,
+            braid: 25,
+            difficulty: Difficulty::Hard,
+            placement: Placement::CentreToBorder,
+            one_way: SeedCode::MAX_ONE_WAY,
+            wrap: Wrap::Mobius,
+            shape: Shape::Floors,
+        };
+        let encoded = code.encode();
+        assert_eq!(27, encoded.len());
+        assert!(encoded.starts_with('1'));
+        let sloppy = encoded.replace('-', "").replace('1', "l").to_lowercase();
+        assert_eq!(Ok(code), SeedCode::decode(&sloppy));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let invalid = Err(CodeError::Invalid);
+        assert_eq!(invalid, SeedCode::decode(""));
+        assert_eq!(invalid, SeedCode::decode("0000000-0000000-000000U"));
+        assert_eq!(invalid, SeedCode::decode("0000000-0000000-00000000"));
+        // zero rows and columns
+        assert_eq!(invalid, SeedCode::decode("0000000-0000000-0000000"));
+        // spare bits set
+        assert_eq!(invalid, SeedCode::decode("Z000000-0000000-0000000"));
+        // a version, but spare bits set
+        assert_eq!(invalid, SeedCode::decode("1Z0000-000000-000000-000000"));
+    }
+
+    #[test]
+    fn test_decode_other_versions() {
+        let code = SeedCode::decode("0000000-00000AG-1R1R000").unwrap();
+        let newer = code.encode().replacen('1', "2", 1);
+        assert_eq!(Err(CodeError::Newer), SeedCode::decode(&newer));
+        // braid and difficulty both came in 20 character codes, so which
+        // one a code has can't be told
+        assert_eq!(
+            Err(CodeError::Old),
+            SeedCode::decode("00000-0000A-G1R1R-00000")
+        );
+        // the very first codes only had a seed, size and generator
+        let first = SeedCode::decode("000000-000002-M0E0E1").unwrap();
+        assert_eq!(42, first.seed);
+        assert_eq!((7, 7), (first.n_rows, first.n_cols));
+        assert_eq!(Generator::Kruskal, first.generator);
+        assert_eq!((0, Difficulty::Any), (first.braid, first.difficulty));
+    }
+
+    #[test]
+    fn test_decode_before_one_way() {
+        // a code from before one-way doors, which left those bits clear
+        let code = SeedCode::decode("0000000-00000AG-1R1R000").unwrap();
+        assert_eq!(42, code.seed);
+        assert_eq!((7, 7), (code.n_rows, code.n_cols));
+        assert_eq!(0, code.one_way);
+    }
+
+    #[test]
+    fn test_decode_before_wrap() {
+        // a version 1 code from before the wrap, which left those bits clear
+        let code = SeedCode::decode("100000-000000-000AG1-R1R000").unwrap();
+        assert_eq!(42, code.seed);
+        assert_eq!((7, 7), (code.n_rows, code.n_cols));
+        assert_eq!(Wrap::None, code.wrap);
+        assert_eq!(Shape::Square, code.shape);
+        let torus = SeedCode {
+            wrap: Wrap::Torus,
+            ..code
+        };
+        assert_eq!("101000-000000-000AG1-R1R000", torus.encode());
+    }
+}
@@ -1,112 +1,1747 @@
-use crate::{Direction, maze::Maze};
-use multid::{BoundedIx2, iterators::V2Indices};
-use rand::{Rng, rngs::ThreadRng, seq::IndexedRandom};
-use std::collections::BTreeSet;
-pub fn seed_doors_naive<const N_ROWS: usize, const N_COLS: usize>(
-    maze: &mut Maze<N_ROWS, N_COLS>,
-    rng: &mut ThreadRng,
-) {
-    for ix in V2Indices::<N_ROWS, N_COLS>::new() {
-        while !maze.rooms[ix].doors.any_open() {
+use crate::{
+    Direction,
+    grid::Ix,
+    maze::{
+        DoorState, Doors, Maze, Room, analysis::distances, floors::Floors, topology::Topology,
+        weave::RoomKind, wrap::Wrap,
+    },
+};
+use rand::{
+    Rng, RngCore,
+    seq::{IndexedRandom, SliceRandom},
+};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// A maze generation algorithm that carves doors into a freshly made `Maze`.
+/// It takes any RNG behind a `dyn`, so seeders can be picked and swapped at
+/// runtime; `Generator` lists the ones in this module.
+pub trait Seeder {
+    fn name(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+    fn seed(&self, maze: &mut Maze, rng: &mut dyn RngCore);
+}
+
+/// A `Seeder` for each of the `seed_doors_*` functions below that only needs
+/// the maze and RNG. Those that don't `wrap` carve the grid by rows or by
+/// splitting it in two, so they're given one whose edges don't wrap.
+macro_rules! seeders {
+    ($($ty:ident { $name:literal, $description:literal, $seed:ident, wrap: $wrap:literal })*) => {
+        $(
+            #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+            pub struct $ty;
+
+            impl Seeder for $ty {
+                fn name(&self) -> &'static str {
+                    $name
+                }
+                fn description(&self) -> &'static str {
+                    $description
+                }
+                fn seed(&self, maze: &mut Maze, mut rng: &mut dyn RngCore) {
+                    let wrap = maze.wrap;
+                    if !$wrap {
+                        maze.wrap = Wrap::None;
+                    }
+                    $seed(maze, &mut rng);
+                    maze.wrap = wrap;
+                }
+            }
+        )*
+    };
+}
+
+seeders! {
+    Naive {
+        "naive",
+        "random doors; some rooms may be unreachable",
+        seed_doors_naive,
+        wrap: true
+    }
+    Path {
+        "path",
+        "a random walk to the goal, plus a door out of every other room",
+        seed_doors_path,
+        wrap: true
+    }
+    Backtrack {
+        "recursive backtracker",
+        "long winding corridors with few dead ends",
+        seed_doors_backtrack,
+        wrap: true
+    }
+    Kruskal {
+        "kruskal",
+        "lots of short dead ends",
+        seed_doors_kruskal,
+        wrap: true
+    }
+    Wilson {
+        "wilson",
+        "uniformly random, with no bias in texture",
+        seed_doors_wilson,
+        wrap: true
+    }
+    Prim {
+        "prim",
+        "many short dead ends radiating from the start",
+        seed_doors_prim,
+        wrap: true
+    }
+    Eller {
+        "eller",
+        "built one row at a time",
+        seed_doors_eller,
+        wrap: false
+    }
+    Division {
+        "recursive division",
+        "long straight walls and chamber-like rooms",
+        seed_doors_division,
+        wrap: false
+    }
+    HuntAndKill {
+        "hunt-and-kill",
+        "long winding corridors, like the backtracker",
+        seed_doors_hunt_and_kill,
+        wrap: true
+    }
+    AldousBroder {
+        "aldous-broder",
+        "uniformly random, but slow to generate",
+        seed_doors_aldous_broder,
+        wrap: true
+    }
+    BinaryTree {
+        "binary tree",
+        "open north and west edges, with a diagonal bias",
+        seed_doors_binary_tree,
+        wrap: false
+    }
+    Sidewinder {
+        "sidewinder",
+        "open north edge, with vertical bias",
+        seed_doors_sidewinder,
+        wrap: false
+    }
+    Weave {
+        "weave",
+        "winding corridors that pass under each other",
+        seed_doors_weave,
+        wrap: true
+    }
+}
+
+/// The growing tree seeder, which picks the next room to grow from by `0`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct GrowingTree(pub CellSelection);
+
+impl Seeder for GrowingTree {
+    fn name(&self) -> &'static str {
+        "growing tree"
+    }
+    fn description(&self) -> &'static str {
+        "a mix of winding corridors and short branches"
+    }
+    fn seed(&self, maze: &mut Maze, mut rng: &mut dyn RngCore) {
+        seed_doors_growing_tree(maze, &mut rng, self.0);
+    }
+}
+
+/// Registry of every generator in this module, so one can be picked at runtime.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Generator {
+    Naive,
+    Path,
+    #[default]
+    Backtrack,
+    Kruskal,
+    Wilson,
+    GrowingTree(CellSelection),
+    Prim,
+    Eller,
+    Division,
+    HuntAndKill,
+    AldousBroder,
+    BinaryTree,
+    Sidewinder,
+    Weave,
+}
+
+impl Generator {
+    pub const ALL: [Generator; 14] = [
+        Generator::Backtrack,
+        Generator::Kruskal,
+        Generator::Wilson,
+        Generator::Prim,
+        Generator::GrowingTree(CellSelection::Weighted {
+            newest: 1,
+            random: 1,
+            oldest: 0,
+        }),
+        Generator::Eller,
+        Generator::Division,
+        Generator::HuntAndKill,
+        Generator::AldousBroder,
+        Generator::BinaryTree,
+        Generator::Sidewinder,
+        Generator::Path,
+        Generator::Naive,
+        Generator::Weave,
+    ];
+
+    /// Position in `Generator::ALL`; generators not listed there count as the first.
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|g| g == self).unwrap_or(0)
+    }
+    pub fn from_index(ix: usize) -> Option<Self> {
+        Self::ALL.get(ix).copied()
+    }
+    /// The generator after this one in `Generator::ALL`, wrapping around.
+    pub fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+    /// The generator before this one in `Generator::ALL`, wrapping around.
+    pub fn previous(&self) -> Self {
+        Self::ALL[(self.index() + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+}
+
+impl Generator {
+    /// The seeder this stands for.
+    pub fn seeder(&self) -> Box<dyn Seeder> {
+        match *self {
+            Generator::Naive => Box::new(Naive),
+            Generator::Path => Box::new(Path),
+            Generator::Backtrack => Box::new(Backtrack),
+            Generator::Kruskal => Box::new(Kruskal),
+            Generator::Wilson => Box::new(Wilson),
+            Generator::GrowingTree(selection) => Box::new(GrowingTree(selection)),
+            Generator::Prim => Box::new(Prim),
+            Generator::Eller => Box::new(Eller),
+            Generator::Division => Box::new(Division),
+            Generator::HuntAndKill => Box::new(HuntAndKill),
+            Generator::AldousBroder => Box::new(AldousBroder),
+            Generator::BinaryTree => Box::new(BinaryTree),
+            Generator::Sidewinder => Box::new(Sidewinder),
+            Generator::Weave => Box::new(Weave),
+        }
+    }
+}
+
+impl Seeder for Generator {
+    fn name(&self) -> &'static str {
+        self.seeder().name()
+    }
+    fn description(&self) -> &'static str {
+        self.seeder().description()
+    }
+    fn seed(&self, maze: &mut Maze, rng: &mut dyn RngCore) {
+        self.seeder().seed(maze, rng)
+    }
+}
+
+pub fn seed_doors_naive(maze: &mut Maze, rng: &mut impl Rng) {
+    for ix in maze.size().indices() {
+        // a lone room has no doors to open
+        if maze.room(ix).available_directions().next().is_none() {
+            continue;
+        }
+        while !maze.room(ix).doors.any_open() {
             if rng.random_bool(0.5) {
-                maze.open_north(ix);
+                maze.open(ix, Direction::North);
             }
             if rng.random_bool(0.5) {
-                maze.open_south(ix);
+                maze.open(ix, Direction::South);
             }
             if rng.random_bool(0.5) {
-                maze.open_east(ix);
+                maze.open(ix, Direction::East);
             }
             if rng.random_bool(0.5) {
-                maze.open_west(ix);
+                maze.open(ix, Direction::West);
             }
         }
     }
 }
 
-pub fn seed_doors_path<const N_ROWS: usize, const N_COLS: usize>(
-    maze: &mut Maze<N_ROWS, N_COLS>,
-    rng: &mut ThreadRng,
-) {
-    let mut all_visited: BTreeSet<BoundedIx2<N_ROWS, N_COLS>> = BTreeSet::new();
+pub fn seed_doors_path(maze: &mut Maze, rng: &mut impl Rng) {
+    let mut all_visited: BTreeSet<Ix> = BTreeSet::new();
+    // a stuck walk is picked up again from where it got stuck rather than from
+    // the start, or on big mazes it'd practically never reach the goal
+    let mut start = maze.current_ix;
     'outer: loop {
-        let mut visited: BTreeSet<BoundedIx2<N_ROWS, N_COLS>> = BTreeSet::new();
-        let mut curr: BoundedIx2<N_ROWS, N_COLS> = maze.current_ix;
+        let mut visited: BTreeSet<Ix> = BTreeSet::new();
+        let mut curr: Ix = start;
         loop {
             if curr == maze.goal {
                 break 'outer;
             }
             visited.insert(curr);
-            let available: Vec<Direction> = maze.rooms[curr]
-                .all_doors()
-                .filter_map::<Direction, _>(|(dir, _)| match dir {
-                    Direction::North => curr.north().and_then(|ix| {
-                        if visited.contains(&ix) {
-                            None
-                        } else {
-                            Some(dir)
-                        }
-                    }),
-                    Direction::East => curr.east().and_then(|ix| {
-                        if visited.contains(&ix) {
-                            None
-                        } else {
-                            Some(dir)
-                        }
-                    }),
-                    Direction::South => curr.south().and_then(|ix| {
-                        if visited.contains(&ix) {
-                            None
-                        } else {
-                            Some(dir)
-                        }
-                    }),
-                    Direction::West => curr.west().and_then(|ix| {
-                        if visited.contains(&ix) {
-                            None
-                        } else {
-                            Some(dir)
-                        }
-                    }),
+            let available: Vec<Direction> = maze
+                .room(curr)
+                .available_directions()
+                .filter(|&dir| {
+                    maze.neighbor(curr, dir)
+                        .is_some_and(|ix| !visited.contains(&ix))
                 })
                 .collect();
             match available.choose(rng) {
                 None => {
                     all_visited.append(&mut visited);
+                    start = curr;
                     break;
                 }
-                Some(Direction::North) => {
-                    maze.open_north(curr);
-                    curr = curr.north().unwrap()
-                }
-                Some(Direction::East) => {
-                    maze.open_east(curr);
-                    curr = curr.east().unwrap()
-                }
-                Some(Direction::South) => {
-                    maze.open_south(curr);
-                    curr = curr.south().unwrap()
-                }
-                Some(Direction::West) => {
-                    maze.open_west(curr);
-                    curr = curr.west().unwrap()
+                Some(&dir) => {
+                    maze.open(curr, dir);
+                    curr = maze.neighbor(curr, dir).unwrap();
                 }
             }
         }
     }
-    for ix in V2Indices::<N_ROWS, N_COLS>::new() {
-        if !all_visited.contains(&ix) {
-            match maze.rooms[ix]
+    for ix in maze.size().indices() {
+        if !all_visited.contains(&ix)
+            && let Some(&dir) = maze
+                .room(ix)
                 .available_directions()
                 .collect::<Vec<Direction>>()
                 .choose(rng)
+        {
+            maze.open(ix, dir);
+        }
+    }
+}
+
+/// Wilson's algorithm: like `seed_doors_path`, it random-walks until it reaches
+/// the carved part of the maze, but loops are erased rather than the walk being
+/// abandoned. Every spanning tree of the grid is equally likely.
+pub fn seed_doors_wilson(maze: &mut Maze, rng: &mut impl Rng) {
+    let mut in_tree: BTreeSet<Ix> = BTreeSet::new();
+    in_tree.insert(maze.goal);
+    for start in maze.mask.active_rooms().collect::<Vec<_>>() {
+        // only the last exit taken from each room is kept, which erases loops
+        let mut exits: BTreeMap<Ix, Direction> = BTreeMap::new();
+        let mut curr = start;
+        while !in_tree.contains(&curr) {
+            let available: Vec<Direction> = maze.room(curr).available_directions().collect();
+            let dir = *available.choose(rng).unwrap();
+            exits.insert(curr, dir);
+            curr = maze.neighbor(curr, dir).unwrap();
+        }
+        let mut curr = start;
+        while !in_tree.contains(&curr) {
+            let dir = exits[&curr];
+            maze.open(curr, dir);
+            in_tree.insert(curr);
+            curr = maze.neighbor(curr, dir).unwrap();
+        }
+    }
+}
+
+/// Eller's algorithm, producing the maze one row of rooms at a time so only a
+/// single row of state is kept no matter how many rows are generated.
+///
+/// Call `next_row` as many times as needed, then `last_row` once to close the
+/// maze off; the rows it returns are connected and loop-free.
+pub struct EllerRows {
+    n_cols: usize,
+    row: usize,
+    sets: Vec<usize>,
+    north_open: Vec<bool>,
+    next_set: usize,
+}
+
+impl EllerRows {
+    pub fn new(n_cols: usize) -> Self {
+        Self {
+            n_cols,
+            row: 0,
+            sets: (0..n_cols).collect(),
+            north_open: vec![false; n_cols],
+            next_set: n_cols,
+        }
+    }
+    pub fn next_row(&mut self, rng: &mut impl Rng) -> Vec<Room> {
+        let west_open = self.join_row(rng, false);
+        let south_open = self.drop_south(rng);
+        let rooms = self.build_row(&west_open, Some(&south_open));
+        self.advance(south_open);
+        rooms
+    }
+    /// The final row, with every remaining set joined and no south doors.
+    pub fn last_row(&mut self, rng: &mut impl Rng) -> Vec<Room> {
+        let west_open = self.join_row(rng, true);
+        let rooms = self.build_row(&west_open, None);
+        self.row += 1;
+        rooms
+    }
+    fn join_row(&mut self, rng: &mut impl Rng, join_all: bool) -> Vec<bool> {
+        let mut west_open = vec![false; self.n_cols];
+        for (col, open) in west_open.iter_mut().enumerate().skip(1) {
+            let (a, b) = (self.sets[col - 1], self.sets[col]);
+            if a != b && (join_all || rng.random_bool(0.5)) {
+                *open = true;
+                for set in self.sets.iter_mut() {
+                    if *set == b {
+                        *set = a;
+                    }
+                }
+            }
+        }
+        west_open
+    }
+    fn drop_south(&mut self, rng: &mut impl Rng) -> Vec<bool> {
+        let mut south_open = vec![false; self.n_cols];
+        let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (col, &set) in self.sets.iter().enumerate() {
+            members.entry(set).or_default().push(col);
+        }
+        for cols in members.values() {
+            for &col in cols {
+                south_open[col] = rng.random_bool(0.5);
+            }
+            // every set needs a way down or it'd be cut off from the rest
+            if !cols.iter().any(|&col| south_open[col]) {
+                south_open[*cols.choose(rng).unwrap()] = true;
+            }
+        }
+        south_open
+    }
+    fn advance(&mut self, south_open: Vec<bool>) {
+        for (col, &open) in south_open.iter().enumerate() {
+            if !open {
+                self.sets[col] = self.next_set;
+                self.next_set += 1;
+            }
+        }
+        self.north_open = south_open;
+        self.row += 1;
+    }
+    fn build_row(&self, west_open: &[bool], south_open: Option<&[bool]>) -> Vec<Room> {
+        let state = |open: bool| {
+            if open {
+                DoorState::Open
+            } else {
+                DoorState::Closed
+            }
+        };
+        (0..self.n_cols)
+            .map(|col| Room {
+                row: self.row,
+                col,
+                doors: Doors {
+                    north: (self.row > 0).then(|| state(self.north_open[col])),
+                    east: (col + 1 < self.n_cols).then(|| state(west_open[col + 1])),
+                    south: south_open.map(|s| state(s[col])),
+                    west: (col > 0).then(|| state(west_open[col])),
+                },
+                key: None,
+                wrapped: [false; 4],
+                kind: RoomKind::Plain,
+            })
+            .collect()
+    }
+}
+
+/// Fills `maze` row by row with `EllerRows`.
+pub fn seed_doors_eller(maze: &mut Maze, rng: &mut impl Rng) {
+    let mut rows = EllerRows::new(maze.n_cols());
+    for row in 0..maze.n_rows() {
+        let rooms = if row + 1 == maze.n_rows() {
+            rows.last_row(rng)
+        } else {
+            rows.next_row(rng)
+        };
+        for (col, room) in rooms.iter().enumerate() {
+            let ix = maze.size().ix(row, col).unwrap();
+            if room.doors.east == Some(DoorState::Open) {
+                maze.open(ix, Direction::East);
+            }
+            if room.doors.south == Some(DoorState::Open) {
+                maze.open(ix, Direction::South);
+            }
+        }
+    }
+    connect_masked(maze, rng);
+}
+
+/// Recursive division: opens every interior door, then repeatedly splits the
+/// grid with a wall that has a single gap in it, leaving long straight walls
+/// and chamber-like rooms.
+pub fn seed_doors_division(maze: &mut Maze, rng: &mut impl Rng) {
+    for ix in maze.size().indices() {
+        maze.open(ix, Direction::East);
+        maze.open(ix, Direction::South);
+    }
+    let size = maze.size();
+    divide(maze, rng, 0, 0, size.n_rows, size.n_cols);
+    connect_masked(maze, rng);
+}
+
+fn divide(
+    maze: &mut Maze,
+    rng: &mut impl Rng,
+    top: usize,
+    left: usize,
+    height: usize,
+    width: usize,
+) {
+    if height < 2 && width < 2 {
+        return;
+    }
+    let horizontal = if height == width {
+        rng.random_bool(0.5)
+    } else {
+        height > width
+    };
+    if horizontal {
+        // wall along the south side of row `top + at - 1`
+        let at = rng.random_range(1..height);
+        let gap = rng.random_range(0..width);
+        for col in (left..left + width).filter(|&col| col != left + gap) {
+            let ix = maze.size().ix(top + at - 1, col).unwrap();
+            maze.close(ix, Direction::South);
+        }
+        divide(maze, rng, top, left, at, width);
+        divide(maze, rng, top + at, left, height - at, width);
+    } else {
+        // wall along the east side of column `left + at - 1`
+        let at = rng.random_range(1..width);
+        let gap = rng.random_range(0..height);
+        for row in (top..top + height).filter(|&row| row != top + gap) {
+            let ix = maze.size().ix(row, left + at - 1).unwrap();
+            maze.close(ix, Direction::East);
+        }
+        divide(maze, rng, top, left, height, at);
+        divide(maze, rng, top, left + at, height, width - at);
+    }
+}
+
+/// Hunt-and-kill: random-walks into unvisited rooms until stuck, then scans
+/// for the first unvisited room next to the carved area and continues from there.
+pub fn seed_doors_hunt_and_kill(maze: &mut Maze, rng: &mut impl Rng) {
+    let mut visited: BTreeSet<Ix> = BTreeSet::new();
+    let mut curr = Some(maze.current_ix);
+    while let Some(ix) = curr {
+        visited.insert(ix);
+        let available: Vec<(Direction, Ix)> = maze
+            .room(ix)
+            .available_directions()
+            .filter_map(|dir| maze.neighbor(ix, dir).map(|next| (dir, next)))
+            .filter(|(_, next)| !visited.contains(next))
+            .collect();
+        curr = match available.choose(rng) {
+            Some(&(dir, next)) => {
+                maze.open(ix, dir);
+                Some(next)
+            }
+            None => hunt(maze, rng, &visited),
+        };
+    }
+}
+
+fn hunt(maze: &mut Maze, rng: &mut impl Rng, visited: &BTreeSet<Ix>) -> Option<Ix> {
+    for ix in maze.size().indices().filter(|ix| !visited.contains(ix)) {
+        let carved: Vec<Direction> = maze
+            .room(ix)
+            .available_directions()
+            .filter(|&dir| {
+                maze.neighbor(ix, dir)
+                    .is_some_and(|next| visited.contains(&next))
+            })
+            .collect();
+        if let Some(&dir) = carved.choose(rng) {
+            maze.open(ix, dir);
+            return Some(ix);
+        }
+    }
+    None
+}
+
+/// Aldous-Broder: a plain random walk that opens a door whenever it steps into
+/// a room for the first time. Slow, but every spanning tree is equally likely.
+pub fn seed_doors_aldous_broder(maze: &mut Maze, rng: &mut impl Rng) {
+    let mut visited: BTreeSet<Ix> = BTreeSet::new();
+    let mut curr = maze.current_ix;
+    visited.insert(curr);
+    let rooms = maze.mask.active_count();
+    while visited.len() < rooms {
+        let available: Vec<Direction> = maze.room(curr).available_directions().collect();
+        let dir = *available.choose(rng).unwrap();
+        let next = maze.neighbor(curr, dir).unwrap();
+        if visited.insert(next) {
+            maze.open(curr, dir);
+        }
+        curr = next;
+    }
+}
+
+/// Binary tree: every room opens either its north or its west door, so the
+/// north row and west column are always single open corridors.
+pub fn seed_doors_binary_tree(maze: &mut Maze, rng: &mut impl Rng) {
+    for ix in maze.size().indices() {
+        let available: Vec<Direction> = maze
+            .room(ix)
+            .available_directions()
+            .filter(|dir| matches!(dir, Direction::North | Direction::West))
+            .collect();
+        if let Some(&dir) = available.choose(rng) {
+            maze.open(ix, dir);
+        }
+    }
+    connect_masked(maze, rng);
+}
+
+/// Sidewinder: carves each row into east-west runs and opens one north door
+/// out of every run, so the north row is always a single open corridor.
+pub fn seed_doors_sidewinder(maze: &mut Maze, rng: &mut impl Rng) {
+    let mut run: Vec<Ix> = Vec::with_capacity(maze.n_cols());
+    for ix in maze.size().indices() {
+        run.push(ix);
+        let at_east_edge = ix.east().is_none();
+        let at_north_edge = ix.north().is_none();
+        if at_east_edge || (!at_north_edge && rng.random_bool(0.5)) {
+            if !at_north_edge {
+                maze.open(*run.choose(rng).unwrap(), Direction::North);
+            }
+            run.clear();
+        } else {
+            maze.open(ix, Direction::East);
+        }
+    }
+    connect_masked(maze, rng);
+}
+
+/// Braiding: removes dead ends by giving each room with a single open door a
+/// second one, with probability `percent`/100. A neighbor that's also a dead
+/// end is preferred, so one new door removes two dead ends.
+pub fn braid(maze: &mut Maze, rng: &mut impl Rng, percent: u8) {
+    let dead_ends = dead_ends(maze);
+    braid_dead_ends(maze, rng, dead_ends, |rng, _| {
+        rng.random_ratio(u32::from(percent.min(100)), 100)
+    });
+}
+
+/// Braids dead ends picked at random until no more than `max` are left, or
+/// none of the rest can be.
+pub fn braid_down_to(maze: &mut Maze, rng: &mut impl Rng, max: usize) {
+    let mut dead_ends = dead_ends(maze);
+    if dead_ends.len() <= max {
+        return;
+    }
+    dead_ends.shuffle(rng);
+    braid_dead_ends(maze, rng, dead_ends, |_, left| left > max);
+}
+
+fn is_dead_end(maze: &Maze, ix: Ix) -> bool {
+    maze.room(ix).open_count() == 1
+}
+
+fn dead_ends(maze: &Maze) -> Vec<Ix> {
+    maze.size()
+        .indices()
+        .filter(|&ix| is_dead_end(maze, ix))
+        .collect()
+}
+
+/// Gives each of `dead_ends` that's still one a second door, if `pick` says
+/// to, given how many dead ends are left.
+fn braid_dead_ends<R: Rng>(
+    maze: &mut Maze,
+    rng: &mut R,
+    dead_ends: Vec<Ix>,
+    mut pick: impl FnMut(&mut R, usize) -> bool,
+) {
+    let mut left = dead_ends.len();
+    for ix in dead_ends {
+        // an earlier pass may already have opened a door into this one
+        if !is_dead_end(maze, ix) || !pick(rng, left) {
+            continue;
+        }
+        let closed: Vec<(Direction, Ix)> = maze
+            .room(ix)
+            .all_doors()
+            .filter(|&(_, st)| st == DoorState::Closed)
+            .filter_map(|(dir, _)| maze.neighbor(ix, dir).map(|next| (dir, next)))
+            .collect();
+        let dead: Vec<(Direction, Ix)> = closed
+            .iter()
+            .filter(|&&(_, next)| is_dead_end(maze, next))
+            .copied()
+            .collect();
+        let chosen = if dead.is_empty() {
+            closed.choose(rng)
+        } else {
+            dead.choose(rng)
+        };
+        if let Some(&(dir, next)) = chosen {
+            left -= if is_dead_end(maze, next) { 2 } else { 1 };
+            maze.open(ix, dir);
+        }
+    }
+}
+
+/// Turns `percent`/100 of the open doors into one-way doors, as far as that can
+/// be done without trapping the player: every room they can get to from the
+/// start must still lead to the goal. Some rooms may end up out of reach, which
+/// makes the doors out of them a red herring rather than a trap, and
+/// `Maze::validate` accepts them.
+pub fn one_way(maze: &mut Maze, rng: &mut impl Rng, percent: u8) {
+    let mut doors: Vec<(Ix, Direction)> = maze
+        .size()
+        .indices()
+        .flat_map(|ix| [(ix, Direction::East), (ix, Direction::South)])
+        .filter(|&(ix, dir)| maze.door(ix, dir) == Some(DoorState::Open))
+        // a crossing's doors are left alone, so the corridor underneath
+        // can always be passed both ways
+        .filter(|&(ix, dir)| {
+            maze.kind(ix) == RoomKind::Plain
+                && maze
+                    .neighbor(ix, dir)
+                    .is_some_and(|next| maze.kind(next) == RoomKind::Plain)
+        })
+        .collect();
+    let target = doors.len() * usize::from(percent.min(100)) / 100;
+    if target == 0 {
+        return;
+    }
+    doors.shuffle(rng);
+    // Only doors into a room that has another, shorter, way on to the goal are
+    // made one-way, so none of these distances change as doors are.
+    let to_goal = steps_to_goal(maze);
+    let closer = |maze: &Maze, from: Ix, into: Ix| {
+        into == maze.goal
+            || maze.open_neighbors(into).any(|next| {
+                next != from && to_goal[next.flat()].is_some_and(|d| Some(d) < to_goal[into.flat()])
+            })
+    };
+    let mut made = 0;
+    for (ix, dir) in doors {
+        if made == target {
+            break;
+        }
+        let next = maze.neighbor(ix, dir).unwrap();
+        let ways = if rng.random_bool(0.5) {
+            [(ix, dir, next), (next, dir.opposite(), ix)]
+        } else {
+            [(next, dir.opposite(), ix), (ix, dir, next)]
+        };
+        if let Some(&(from, way, _)) = ways
+            .iter()
+            .find(|&&(from, _, into)| closer(maze, from, into))
+        {
+            maze.open_one_way(from, way);
+            made += 1;
+        }
+    }
+}
+
+/// How many steps each room is from the goal, going through doors the way
+/// they can be passed. `None` for rooms that can't get there.
+fn steps_to_goal(maze: &Maze) -> Vec<Option<usize>> {
+    let mut steps = vec![None; maze.size().len()];
+    steps[maze.goal.flat()] = Some(0);
+    let mut queue = VecDeque::from([maze.goal]);
+    while let Some(ix) = queue.pop_front() {
+        let dist = steps[ix.flat()].unwrap();
+        // doors that can be passed into this room from the other side
+        for (dir, st) in maze.room(ix).all_doors() {
+            if matches!(st, DoorState::Open | DoorState::OneWayIn)
+                && let Some(next) = maze.through(ix, dir)
+                && steps[next.flat()].is_none()
             {
-                Some(Direction::North) => maze.open_north(ix),
-                Some(Direction::East) => maze.open_east(ix),
-                Some(Direction::South) => maze.open_south(ix),
-                Some(Direction::West) => maze.open_west(ix),
+                steps[next.flat()] = Some(dist + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    steps
+}
+
+/// Recursive backtracker: a randomized depth-first search from `maze.current_ix`
+/// that carves a perfect maze (every room reachable, exactly one route between
+/// any two rooms).
+pub fn seed_doors_backtrack(maze: &mut Maze, rng: &mut impl Rng) {
+    seed_backtrack(maze, rng);
+}
+
+/// The recursive backtracker, but free to dig under a straight corridor it
+/// has already made, to an unvisited room on the far side. The room it digs
+/// under becomes a crossing.
+pub fn seed_doors_weave(maze: &mut Maze, rng: &mut impl Rng) {
+    let mut visited: BTreeSet<Ix> = BTreeSet::new();
+    let mut stack: Vec<Ix> = vec![maze.current_ix];
+    visited.insert(maze.current_ix);
+    while let Some(&curr) = stack.last() {
+        let available: Vec<(Direction, Ix, bool)> = maze
+            .room(curr)
+            .available_directions()
+            .filter_map(|dir| {
+                let next = maze.neighbor(curr, dir)?;
+                if !visited.contains(&next) {
+                    return Some((dir, next, false));
+                }
+                let past = maze.neighbor(next, dir)?;
+                (maze.can_tunnel(curr, dir) && !visited.contains(&past))
+                    .then_some((dir, past, true))
+            })
+            .collect();
+        match available.choose(rng) {
+            None => {
+                stack.pop();
+            }
+            Some(&(dir, next, under)) => {
+                if under {
+                    maze.tunnel(curr, dir);
+                } else {
+                    maze.open(curr, dir);
+                }
+                visited.insert(next);
+                stack.push(next);
+            }
+        }
+    }
+}
+
+/// Joins each floor to the one above with up to `per_floor` flights of stairs,
+/// in different random rooms that the player can get to from where they
+/// arrive on that floor. The last flight up goes from rooms that lead on to
+/// the goal where there are any. A floor that isn't all connected can leave
+/// the goal cut off from every room the stairs could go to, and then the goal
+/// moves to the room furthest from where the first flight comes out.
+pub fn seed_stairs(floors: &mut Floors, rng: &mut impl Rng, per_floor: usize) {
+    let top = floors.floors.len() - 1;
+    let mut arrivals = vec![floors.floors[0].current_ix];
+    for floor in 0..top {
+        let maze = &floors.floors[floor];
+        let reached: BTreeSet<Ix> = arrivals
+            .iter()
+            .flat_map(|&ix| maze.reachable(ix, &BTreeSet::new()))
+            .collect();
+        let mut rooms: Vec<Ix> = reached.into_iter().collect();
+        if floor + 1 == top {
+            let to_goal = steps_to_goal(&floors.floors[top]);
+            let leads_on: Vec<Ix> = rooms
+                .iter()
+                .copied()
+                .filter(|ix| to_goal[ix.flat()].is_some())
+                .collect();
+            if !leads_on.is_empty() {
+                rooms = leads_on;
+            }
+        }
+        arrivals = rooms
+            .choose_multiple(rng, per_floor.max(1))
+            .copied()
+            .collect();
+        for &ix in &arrivals {
+            floors.add_stairs(floor, ix);
+        }
+    }
+    let maze = &mut floors.floors[top];
+    let to_goal = steps_to_goal(maze);
+    if top > 0 && arrivals.iter().all(|ix| to_goal[ix.flat()].is_none()) {
+        let goal = distances(maze, arrivals[0])
+            .into_iter()
+            .max_by_key(|&(_, d)| d)
+            .map_or(arrivals[0], |(ix, _)| ix);
+        maze.goal = goal;
+        floors.goal = (top, goal);
+    }
+}
+
+/// The recursive backtracker for a maze of any shape.
+pub fn seed_backtrack<T: Topology>(maze: &mut T, rng: &mut impl Rng) {
+    let mut visited: BTreeSet<Ix> = BTreeSet::new();
+    let mut stack: Vec<Ix> = vec![maze.start()];
+    visited.insert(maze.start());
+    while let Some(&curr) = stack.last() {
+        let available: Vec<(T::Dir, Ix)> = maze
+            .exits(curr)
+            .into_iter()
+            .filter(|(_, ix)| !visited.contains(ix))
+            .collect();
+        match available.choose(rng) {
+            None => {
+                stack.pop();
+            }
+            Some(&(dir, next)) => {
+                maze.open(curr, dir);
+                visited.insert(next);
+                stack.push(next);
+            }
+        }
+    }
+}
+
+/// How `seed_doors_growing_tree` picks the next active room to grow from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CellSelection {
+    /// Most recently added room: long winding corridors, like the backtracker.
+    Newest,
+    /// Uniformly random room: short branchy dead ends, like Prim's.
+    Random,
+    /// Least recently added room: long straight corridors fanning out from the start.
+    Oldest,
+    /// Each strategy picked with probability proportional to its weight.
+    Weighted {
+        newest: u32,
+        random: u32,
+        oldest: u32,
+    },
+}
+
+impl CellSelection {
+    fn pick(&self, len: usize, rng: &mut impl Rng) -> usize {
+        match *self {
+            CellSelection::Newest => len - 1,
+            CellSelection::Random => rng.random_range(0..len),
+            CellSelection::Oldest => 0,
+            CellSelection::Weighted {
+                newest,
+                random,
+                oldest,
+            } => {
+                let total = newest + random + oldest;
+                if total == 0 {
+                    return len - 1;
+                }
+                let roll = rng.random_range(0..total);
+                if roll < newest {
+                    CellSelection::Newest.pick(len, rng)
+                } else if roll < newest + random {
+                    CellSelection::Random.pick(len, rng)
+                } else {
+                    CellSelection::Oldest.pick(len, rng)
+                }
+            }
+        }
+    }
+}
+
+/// Growing tree: keeps a list of active rooms, repeatedly picks one with
+/// `selection` and carves into an unvisited neighbor, retiring rooms that have none.
+pub fn seed_doors_growing_tree(maze: &mut Maze, rng: &mut impl Rng, selection: CellSelection) {
+    let mut visited: BTreeSet<Ix> = BTreeSet::new();
+    let mut active: Vec<Ix> = vec![maze.current_ix];
+    visited.insert(maze.current_ix);
+    while !active.is_empty() {
+        let i = selection.pick(active.len(), rng);
+        let curr = active[i];
+        let available: Vec<(Direction, Ix)> = maze
+            .room(curr)
+            .available_directions()
+            .filter_map(|dir| maze.neighbor(curr, dir).map(|ix| (dir, ix)))
+            .filter(|(_, ix)| !visited.contains(ix))
+            .collect();
+        match available.choose(rng) {
+            None => {
+                active.remove(i);
+            }
+            Some(&(dir, next)) => {
+                maze.open(curr, dir);
+                visited.insert(next);
+                active.push(next);
+            }
+        }
+    }
+}
+
+/// Prim's algorithm, as a growing tree that always selects a random active room.
+pub fn seed_doors_prim(maze: &mut Maze, rng: &mut impl Rng) {
+    seed_doors_growing_tree(maze, rng, CellSelection::Random)
+}
+
+/// Kruskal's algorithm: visits every interior wall in random order and opens it
+/// only when the rooms on either side aren't yet connected. Doors that are
+/// already open count as connections, so on a partly carved maze it only adds
+/// the doors needed to join it up.
+pub fn seed_doors_kruskal(maze: &mut Maze, rng: &mut impl Rng) {
+    let mut walls: Vec<(Ix, Direction)> = Vec::with_capacity(2 * maze.size().len());
+    let mut sets = DisjointSet::new(maze.size().len());
+    for ix in maze.size().indices() {
+        let doors = &maze.room(ix).doors;
+        for (dir, st) in [
+            (Direction::East, doors.east),
+            (Direction::South, doors.south),
+        ] {
+            match st {
+                Some(DoorState::Closed) => walls.push((ix, dir)),
+                // a locked or one-way door joins its rooms as well as an open one does
+                Some(_) => {
+                    sets.union(ix.flat(), maze.neighbor(ix, dir).unwrap().flat());
+                }
                 None => (),
             }
         }
     }
+    walls.shuffle(rng);
+    for (ix, dir) in walls {
+        let other = maze.neighbor(ix, dir).unwrap();
+        if sets.union(ix.flat(), other.flat()) {
+            maze.open(ix, dir);
+        }
+    }
+}
+
+/// Union-find over flattened room indices, with path halving and union by size.
+struct DisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl DisjointSet {
+    fn new(n: usize) -> Self {
+        Self {
+            parents: (0..n).collect(),
+            sizes: vec![1; n],
+        }
+    }
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+    /// Merges the sets containing `a` and `b`, returning `false` if they were
+    /// already the same set.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        true
+    }
+}
+
+/// The row-by-row generators assume every room exists, so with a mask they
+/// can leave the maze in pieces; this joins the pieces back up.
+fn connect_masked(maze: &mut Maze, rng: &mut impl Rng) {
+    if !maze.mask.is_full() {
+        seed_doors_kruskal(maze, rng);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        grid::Size,
+        maze::{
+            MoveError, hex::HexMaze, mask::Mask, placement::Placement, polar::PolarMaze,
+            validation::Problem,
+        },
+    };
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn open_door_pairs(maze: &Maze) -> usize {
+        maze.size()
+            .indices()
+            .map(|ix| maze.room(ix).open_count())
+            .sum::<usize>()
+            / 2
+    }
+
+    fn reachable(maze: &Maze) -> usize {
+        let mut seen: BTreeSet<Ix> = BTreeSet::new();
+        let mut stack = vec![maze.current_ix];
+        seen.insert(maze.current_ix);
+        while let Some(ix) = stack.pop() {
+            for (dir, st) in maze.room(ix).all_doors() {
+                if st == DoorState::Open
+                    && let Some(next) = maze.neighbor(ix, dir)
+                    && seen.insert(next)
+                {
+                    stack.push(next);
+                }
+            }
+        }
+        seen.len()
+    }
+
+    fn assert_perfect(maze: &Maze) {
+        assert_eq!(maze.size().len() - 1, open_door_pairs(maze), "door pairs");
+        assert_eq!(maze.size().len(), reachable(maze), "reachable rooms");
+    }
+
+    #[test]
+    fn test_backtrack_perfect() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            let mut m = Maze::new(7, 7);
+            seed_doors_backtrack(&mut m, &mut rng);
+            assert_perfect(&m);
+            let mut m = Maze::new(3, 9);
+            seed_doors_backtrack(&mut m, &mut rng);
+            assert_perfect(&m);
+        }
+    }
+
+    /// Every room the flood fill through `open_neighbors` from `start` gets to.
+    fn flood<T: Copy + Ord>(start: T, open_neighbors: impl Fn(T) -> Vec<T>) -> usize {
+        reach(start, open_neighbors).len()
+    }
+
+    fn reach<T: Copy + Ord>(start: T, open_neighbors: impl Fn(T) -> Vec<T>) -> BTreeSet<T> {
+        let mut seen = BTreeSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(ix) = stack.pop() {
+            stack.extend(
+                open_neighbors(ix)
+                    .into_iter()
+                    .filter(|&next| seen.insert(next)),
+            );
+        }
+        seen
+    }
+
+    #[test]
+    fn test_backtrack_hex_perfect() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for size in [Size::new(7, 7), Size::new(4, 9), Size::new(1, 5)] {
+            let mut m = HexMaze::new(size);
+            seed_backtrack(&mut m, &mut rng);
+            assert_eq!(size.len() - 1, m.open_door_count());
+            let reached = flood(m.current_ix, |ix| m.open_neighbors(ix).collect());
+            assert_eq!(size.len(), reached);
+        }
+    }
+
+    #[test]
+    fn test_stairs_connect_floors() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let floors: Vec<Maze> = (0..4)
+            .map(|_| {
+                let mut m = Maze::new(5, 5);
+                seed_doors_backtrack(&mut m, &mut rng);
+                m
+            })
+            .collect();
+        let mut floors = Floors::new(floors);
+        seed_stairs(&mut floors, &mut rng, 2);
+        let reached = flood(floors.current(), |pos| floors.open_neighbors(pos));
+        assert_eq!(4 * 25, reached);
+    }
+
+    #[test]
+    fn test_stairs_reach_goal() {
+        for generator in Generator::ALL {
+            for seed in 0..SEEDS {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let floors: Vec<Maze> = (0..3)
+                    .map(|_| {
+                        let mut m = Maze::new(7, 7);
+                        generator.seed(&mut m, &mut rng);
+                        m.place(Placement::Corners, &mut rng);
+                        m
+                    })
+                    .collect();
+                let mut floors = Floors::new(floors);
+                seed_stairs(&mut floors, &mut rng, 2);
+                assert_eq!(floors.goal, (2, floors.floors[2].goal));
+                assert!(
+                    reach(floors.current(), |pos| floors.open_neighbors(pos))
+                        .contains(&floors.goal),
+                    "{generator:?} seed {seed}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_backtrack_polar_perfect() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for rings in [2, 5, 12] {
+            let mut m = PolarMaze::new(rings);
+            seed_backtrack(&mut m, &mut rng);
+            let rooms = m.rooms().count();
+            assert_eq!(rooms - 1, m.open_door_count());
+            let reached = flood(m.current_ix, |ix| m.open_neighbors(ix).collect());
+            assert_eq!(rooms, reached);
+        }
+    }
+
+    #[test]
+    fn test_kruskal_spanning_tree() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..20 {
+            let mut m = Maze::new(7, 7);
+            seed_doors_kruskal(&mut m, &mut rng);
+            assert_perfect(&m);
+            let mut m = Maze::new(1, 5);
+            seed_doors_kruskal(&mut m, &mut rng);
+            assert_perfect(&m);
+            let mut m = Maze::new(8, 3);
+            seed_doors_kruskal(&mut m, &mut rng);
+            assert_perfect(&m);
+        }
+    }
+
+    #[test]
+    fn test_wilson_spanning_tree() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..20 {
+            let mut m = Maze::new(7, 7);
+            seed_doors_wilson(&mut m, &mut rng);
+            assert_perfect(&m);
+            let mut m = Maze::new(2, 6);
+            seed_doors_wilson(&mut m, &mut rng);
+            assert_perfect(&m);
+        }
+    }
+
+    #[test]
+    fn test_growing_tree_perfect() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let selections = [
+            CellSelection::Newest,
+            CellSelection::Random,
+            CellSelection::Oldest,
+            CellSelection::Weighted {
+                newest: 3,
+                random: 1,
+                oldest: 1,
+            },
+            CellSelection::Weighted {
+                newest: 0,
+                random: 0,
+                oldest: 0,
+            },
+        ];
+        for selection in selections {
+            for _ in 0..10 {
+                let mut m = Maze::new(7, 7);
+                seed_doors_growing_tree(&mut m, &mut rng, selection);
+                assert_perfect(&m);
+            }
+        }
+    }
+
+    #[test]
+    fn test_growing_tree_oldest_opens_start_row() {
+        // growing from the oldest room always exhausts (0, 0) first, so the
+        // start room ends up with both of its doors open
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut m = Maze::new(5, 5);
+        seed_doors_growing_tree(&mut m, &mut rng, CellSelection::Oldest);
+        let start = &m.room(m.current_ix).doors;
+        assert_eq!(Some(DoorState::Open), start.east);
+        assert_eq!(Some(DoorState::Open), start.south);
+    }
+
+    #[test]
+    fn test_eller_perfect() {
+        let mut rng = StdRng::seed_from_u64(6);
+        for _ in 0..20 {
+            let mut m = Maze::new(7, 7);
+            seed_doors_eller(&mut m, &mut rng);
+            assert_perfect(&m);
+            let mut m = Maze::new(9, 1);
+            seed_doors_eller(&mut m, &mut rng);
+            assert_perfect(&m);
+        }
+    }
+
+    #[test]
+    fn test_eller_rows_stream() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut rows = EllerRows::new(6);
+        let mut prev = rows.next_row(&mut rng);
+        assert!(prev.iter().all(|r| r.doors.north.is_none()));
+        for i in 0..500 {
+            let next = if i == 499 {
+                rows.last_row(&mut rng)
+            } else {
+                rows.next_row(&mut rng)
+            };
+            for (above, below) in prev.iter().zip(next.iter()) {
+                assert_eq!(above.doors.south, below.doors.north);
+            }
+            assert!(prev.iter().any(|r| r.doors.south == Some(DoorState::Open)));
+            prev = next;
+        }
+        assert!(prev.iter().all(|r| r.doors.south.is_none()));
+    }
+
+    #[test]
+    fn test_division_perfect() {
+        let mut rng = StdRng::seed_from_u64(8);
+        for _ in 0..20 {
+            let mut m = Maze::new(7, 7);
+            seed_doors_division(&mut m, &mut rng);
+            assert_perfect(&m);
+            let mut m = Maze::new(1, 6);
+            seed_doors_division(&mut m, &mut rng);
+            assert_perfect(&m);
+            let mut m = Maze::new(10, 4);
+            seed_doors_division(&mut m, &mut rng);
+            assert_perfect(&m);
+        }
+    }
+
+    fn dead_ends(maze: &Maze) -> usize {
+        maze.size()
+            .indices()
+            .filter(|&ix| maze.room(ix).open_count() == 1)
+            .count()
+    }
+
+    #[test]
+    fn test_hunt_and_kill_perfect() {
+        let mut rng = StdRng::seed_from_u64(9);
+        for _ in 0..20 {
+            let mut m = Maze::new(7, 7);
+            seed_doors_hunt_and_kill(&mut m, &mut rng);
+            assert_perfect(&m);
+            let mut m = Maze::new(4, 11);
+            seed_doors_hunt_and_kill(&mut m, &mut rng);
+            assert_perfect(&m);
+        }
+    }
+
+    #[test]
+    fn test_hunt_and_kill_fewer_dead_ends_than_prim() {
+        // long random walks leave far fewer dead ends than Prim's short branches
+        let mut rng = StdRng::seed_from_u64(10);
+        let (mut hunt, mut prim) = (0, 0);
+        for _ in 0..20 {
+            let mut m = Maze::new(12, 12);
+            seed_doors_hunt_and_kill(&mut m, &mut rng);
+            hunt += dead_ends(&m);
+            let mut m = Maze::new(12, 12);
+            seed_doors_prim(&mut m, &mut rng);
+            prim += dead_ends(&m);
+        }
+        assert!(hunt < prim, "hunt-and-kill {hunt} vs prim {prim}");
+    }
+
+    #[test]
+    fn test_aldous_broder_perfect() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..20 {
+            let mut m = Maze::new(7, 7);
+            seed_doors_aldous_broder(&mut m, &mut rng);
+            assert_perfect(&m);
+            let mut m = Maze::new(1, 4);
+            seed_doors_aldous_broder(&mut m, &mut rng);
+            assert_perfect(&m);
+        }
+    }
+
+    #[test]
+    fn test_aldous_broder_uniform() {
+        // a 2x2 grid has exactly four spanning trees, one per missing wall
+        let mut rng = StdRng::seed_from_u64(12);
+        let mut counts: BTreeMap<(bool, bool, bool, bool), usize> = BTreeMap::new();
+        let (tl, br) = (
+            Size::new(2, 2).ix(0, 0).unwrap(),
+            Size::new(2, 2).ix(1, 1).unwrap(),
+        );
+        for _ in 0..4000 {
+            let mut m = Maze::new(2, 2);
+            seed_doors_aldous_broder(&mut m, &mut rng);
+            let key = (
+                m.room(tl).doors.east == Some(DoorState::Open),
+                m.room(tl).doors.south == Some(DoorState::Open),
+                m.room(br).doors.north == Some(DoorState::Open),
+                m.room(br).doors.west == Some(DoorState::Open),
+            );
+            *counts.entry(key).or_default() += 1;
+        }
+        assert_eq!(4, counts.len());
+        for count in counts.values() {
+            assert!((800..1200).contains(count), "{counts:?}");
+        }
+    }
+
+    #[test]
+    fn test_binary_tree_perfect() {
+        let mut rng = StdRng::seed_from_u64(13);
+        for _ in 0..20 {
+            let mut m = Maze::new(7, 7);
+            seed_doors_binary_tree(&mut m, &mut rng);
+            assert_perfect(&m);
+        }
+    }
+
+    #[test]
+    fn test_binary_tree_open_north_row_and_west_column() {
+        let mut rng = StdRng::seed_from_u64(14);
+        for _ in 0..20 {
+            let mut m = Maze::new(6, 8);
+            seed_doors_binary_tree(&mut m, &mut rng);
+            for ix in Size::new(6, 8).indices() {
+                if ix.north().is_none() && ix.east().is_some() {
+                    assert_eq!(Some(DoorState::Open), m.room(ix).doors.east, "{ix:?}");
+                }
+                if ix.west().is_none() && ix.south().is_some() {
+                    assert_eq!(Some(DoorState::Open), m.room(ix).doors.south, "{ix:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_sidewinder_perfect() {
+        let mut rng = StdRng::seed_from_u64(15);
+        for _ in 0..20 {
+            let mut m = Maze::new(7, 7);
+            seed_doors_sidewinder(&mut m, &mut rng);
+            assert_perfect(&m);
+            let mut m = Maze::new(5, 1);
+            seed_doors_sidewinder(&mut m, &mut rng);
+            assert_perfect(&m);
+        }
+    }
+
+    #[test]
+    fn test_sidewinder_open_north_row_and_one_exit_per_run() {
+        let mut rng = StdRng::seed_from_u64(16);
+        for _ in 0..20 {
+            let mut m = Maze::new(6, 8);
+            seed_doors_sidewinder(&mut m, &mut rng);
+            let mut north_exits = 0;
+            for ix in Size::new(6, 8).indices() {
+                let doors = &m.room(ix).doors;
+                if ix.north().is_none() && ix.east().is_some() {
+                    assert_eq!(Some(DoorState::Open), doors.east, "{ix:?}");
+                }
+                if doors.north == Some(DoorState::Open) {
+                    north_exits += 1;
+                }
+                // a run ends where the east door is closed, and every run
+                // below the north row climbs out through exactly one door
+                if ix.north().is_some() && doors.east != Some(DoorState::Open) {
+                    assert_eq!(1, north_exits, "{ix:?}");
+                }
+                if doors.east != Some(DoorState::Open) {
+                    north_exits = 0;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_braid() {
+        let mut rng = StdRng::seed_from_u64(100);
+        for _ in 0..20 {
+            let mut m = Maze::new(7, 7);
+            seed_doors_backtrack(&mut m, &mut rng);
+            let before = dead_ends(&m);
+            braid(&mut m, &mut rng, 0);
+            assert_eq!(before, dead_ends(&m));
+            braid(&mut m, &mut rng, 50);
+            assert!(dead_ends(&m) <= before);
+            assert_eq!(49, reachable(&m));
+            braid(&mut m, &mut rng, 100);
+            assert_eq!(0, dead_ends(&m));
+        }
+    }
+
+    /// Whether the player can get somewhere, from the start, that they can't get
+    /// to the goal from.
+    fn has_trap(maze: &Maze) -> bool {
+        let size = maze.size();
+        let mut to_goal = vec![false; size.len()];
+        to_goal[maze.goal.flat()] = true;
+        let mut stack = vec![maze.goal];
+        while let Some(ix) = stack.pop() {
+            // a door that can be passed into this room from the other side
+            for (dir, st) in maze.room(ix).all_doors() {
+                if matches!(st, DoorState::Open | DoorState::OneWayIn)
+                    && let Some(next) = maze.neighbor(ix, dir)
+                    && !to_goal[next.flat()]
+                {
+                    to_goal[next.flat()] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        let mut seen = vec![false; size.len()];
+        seen[maze.current_ix.flat()] = true;
+        let mut stack = vec![maze.current_ix];
+        while let Some(ix) = stack.pop() {
+            if !to_goal[ix.flat()] {
+                return true;
+            }
+            for next in maze.open_neighbors(ix) {
+                if !seen[next.flat()] {
+                    seen[next.flat()] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn test_one_way() {
+        let mut rng = StdRng::seed_from_u64(200);
+        for (braid_percent, percent) in [(0, 30), (0, 100), (100, 30), (100, 100)] {
+            for _ in 0..50 {
+                let mut m = Maze::new(7, 7);
+                seed_doors_backtrack(&mut m, &mut rng);
+                braid(&mut m, &mut rng, braid_percent);
+                m.place(Placement::Random, &mut rng);
+                let before = m.open_door_count();
+                one_way(&mut m, &mut rng, percent);
+                assert_eq!(before, m.open_door_count());
+                let one_way_doors = m
+                    .size()
+                    .indices()
+                    .flat_map(|ix| m.room(ix).all_doors())
+                    .filter(|&(_, st)| st == DoorState::OneWayOut)
+                    .count();
+                assert!(one_way_doors > 0);
+                assert!(!has_trap(&m));
+            }
+        }
+    }
+
+    #[test]
+    fn test_has_trap() {
+        let mut m = Maze::new(1, 3);
+        let ix = |col| Size::new(1, 3).ix(0, col).unwrap();
+        m.open(ix(0), Direction::East);
+        m.open(ix(1), Direction::East);
+        m.goal = ix(1);
+        assert!(!has_trap(&m));
+        // the room past the goal can be walked into but not out of
+        m.open_one_way(ix(1), Direction::East);
+        assert!(has_trap(&m));
+        m.open_one_way(ix(2), Direction::West);
+        assert!(!has_trap(&m));
+        m.current_ix = ix(1);
+        assert_eq!(Err(MoveError::OneWay), m.try_move(Direction::East));
+        m.current_ix = ix(2);
+        assert_eq!(Ok(ix(1)), m.try_move(Direction::West));
+    }
+
+    fn check_generators(n_rows: usize, n_cols: usize, seeds: u64) {
+        check_generators_masked(&Mask::full(Size::new(n_rows, n_cols)), seeds);
+    }
+
+    /// Runs every registered generator over `seeds` seeds, checking that the
+    /// perfect-maze generators always make valid spanning trees of the active
+    /// rooms, and that the others at least keep their doors consistent.
+    fn check_generators_masked(mask: &Mask, seeds: u64) {
+        check_generators_wrapped(mask, Wrap::None, seeds);
+    }
+
+    fn check_generators_wrapped(mask: &Mask, wrap: Wrap, seeds: u64) {
+        for generator in Generator::ALL {
+            let perfect = !matches!(generator, Generator::Naive | Generator::Path);
+            for seed in 0..seeds {
+                let mut m = Maze::with_mask(mask.clone());
+                m.wrap = wrap;
+                let mut rng = StdRng::seed_from_u64(seed);
+                generator.seed(&mut m, &mut rng);
+                let problems = match m.validate() {
+                    Ok(()) => vec![],
+                    Err(problems) => problems,
+                };
+                let name = generator.name();
+                assert_eq!(wrap, m.wrap, "{name} {seed}");
+                if perfect {
+                    assert_eq!(Vec::<Problem>::new(), problems, "{name} {seed}");
+                    assert_eq!(
+                        mask.active_count() - 1,
+                        open_door_pairs(&m),
+                        "{name} {seed}"
+                    );
+                } else {
+                    assert!(
+                        problems
+                            .iter()
+                            .all(|p| matches!(p, Problem::Unreachable(_))),
+                        "{name} {seed}: {problems:?}"
+                    );
+                }
+                braid(&mut m, &mut rng, 50);
+                if perfect {
+                    assert_eq!(Ok(()), m.validate(), "braided {name} {seed}");
+                }
+            }
+        }
+    }
+
+    /// Seeds per generator in the quick sweeps. `test_generators_exhaustive`
+    /// runs the same sweeps over far more, and is slow enough in a debug build
+    /// that it only runs when asked for, with `cargo test -- --ignored`.
+    const SEEDS: u64 = 30;
+
+    fn ring_mask() -> Mask {
+        Mask::parse(
+            "
+            ..###..
+            .#####.
+            ###.###
+            ##...##
+            ###.###
+            .#####.
+            ..###..
+            ",
+        )
+        .unwrap()
+    }
+
+    fn ell_mask() -> Mask {
+        Mask::parse("#..\n#..\n#..\n###").unwrap()
+    }
+
+    fn check_generators_all_wraps(seeds: u64) {
+        for wrap in [Wrap::Cylinder, Wrap::Torus, Wrap::Mobius] {
+            check_generators_wrapped(&Mask::full(Size::new(5, 8)), wrap, seeds);
+            check_generators_wrapped(&Mask::full(Size::new(2, 3)), wrap, seeds);
+            check_generators_wrapped(&ell_mask(), wrap, seeds);
+        }
+    }
+
+    #[test]
+    fn test_generators_masked() {
+        check_generators_masked(&ring_mask(), SEEDS);
+        check_generators_masked(&ell_mask(), SEEDS);
+    }
+
+    #[test]
+    fn test_generators_wrapped() {
+        check_generators_all_wraps(SEEDS);
+    }
+
+    #[test]
+    fn test_weave_crossings() {
+        let mut m = Maze::new(12, 12);
+        let mut rng = StdRng::seed_from_u64(3);
+        seed_doors_weave(&mut m, &mut rng);
+        let crossings: Vec<Ix> = m
+            .size()
+            .indices()
+            .filter(|&ix| m.kind(ix) != RoomKind::Plain)
+            .collect();
+        assert!(!crossings.is_empty());
+        for ix in crossings {
+            // a straight corridor on top, and one passing underneath
+            let room = m.room(ix);
+            assert_eq!(2, room.open_count(), "{ix:?}");
+            assert!(
+                room.available_directions()
+                    .all(|dir| !room.kind.is_under(dir))
+            );
+            for dir in Direction::North {
+                assert_eq!(Some(DoorState::Open), m.door(ix, dir), "{ix:?} {dir:?}");
+            }
+        }
+        assert_eq!(Ok(()), m.validate());
+    }
+
+    #[test]
+    fn test_backtrack_crosses_wrap() {
+        let mut m = Maze::new(6, 6);
+        m.wrap = Wrap::Torus;
+        let mut rng = StdRng::seed_from_u64(1);
+        Generator::Backtrack.seed(&mut m, &mut rng);
+        let seams = m
+            .size()
+            .indices()
+            .filter(|&ix| {
+                [Direction::North, Direction::West]
+                    .into_iter()
+                    .any(|dir| m.wraps(ix, dir) && m.door(ix, dir) == Some(DoorState::Open))
+            })
+            .count();
+        assert!(seams > 0);
+    }
+
+    #[test]
+    fn test_seeder_dyn() {
+        // any seeder will do, with any RNG, and the registry hands out the same
+        let seeders: [(&dyn Seeder, Generator); 2] =
+            [(&Kruskal, Generator::Kruskal), (&Eller, Generator::Eller)];
+        for (seeder, generator) in seeders {
+            let mut a = Maze::new(6, 6);
+            a.wrap = Wrap::Torus;
+            let mut b = a.clone();
+            seeder.seed(&mut a, &mut StdRng::seed_from_u64(3));
+            generator.seed(&mut b, &mut StdRng::seed_from_u64(3));
+            assert_eq!(seeder.name(), generator.name());
+            assert_eq!(Wrap::Torus, a.wrap);
+            assert_eq!(Ok(()), a.validate());
+            assert!(a.size().indices().all(|ix| a.room(ix) == b.room(ix)));
+        }
+    }
+
+    #[test]
+    fn test_generators_tiny() {
+        check_generators(1, 1, SEEDS);
+        check_generators(2, 2, SEEDS);
+    }
+
+    #[test]
+    fn test_generators_strips() {
+        check_generators(1, 9, SEEDS);
+        check_generators(9, 1, SEEDS);
+    }
+
+    #[test]
+    fn test_generators_rectangular() {
+        check_generators(5, 8, SEEDS);
+    }
+
+    #[test]
+    fn test_generators_large() {
+        check_generators(12, 12, SEEDS);
+        check_generators(100, 100, 1);
+    }
+
+    #[test]
+    #[ignore = "slow; run with --ignored"]
+    fn test_generators_exhaustive() {
+        check_generators_masked(&ring_mask(), 300);
+        check_generators_masked(&ell_mask(), 1000);
+        check_generators_all_wraps(300);
+        check_generators(1, 1, 2000);
+        check_generators(2, 2, 2000);
+        check_generators(1, 9, 2000);
+        check_generators(9, 1, 2000);
+        check_generators(5, 8, 1000);
+        check_generators(12, 12, 300);
+    }
+
+    #[test]
+    fn test_generators_reproducible() {
+        for generator in Generator::ALL {
+            let mut a = Maze::new(6, 6);
+            generator.seed(&mut a, &mut StdRng::seed_from_u64(42));
+            let mut b = Maze::new(6, 6);
+            generator.seed(&mut b, &mut StdRng::seed_from_u64(42));
+            for ix in Size::new(6, 6).indices() {
+                assert_eq!(a.room(ix).doors, b.room(ix).doors, "{}", generator.name());
+            }
+        }
+    }
+
+    #[test]
+    fn test_generator_cycle() {
+        let mut g = Generator::default();
+        for _ in 0..Generator::ALL.len() {
+            g = g.next();
+        }
+        assert_eq!(Generator::default(), g);
+        assert_eq!(Generator::default(), g.next().previous());
+    }
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut sets = DisjointSet::new(4);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(!sets.union(1, 0));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert_eq!(sets.find(0), sets.find(3));
+    }
 }
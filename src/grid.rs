@@ -0,0 +1,193 @@
+use std::{cmp::Ordering, fmt};
+
+/// The dimensions of a grid of rooms.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Size {
+    pub n_rows: usize,
+    pub n_cols: usize,
+}
+
+impl Size {
+    pub fn new(n_rows: usize, n_cols: usize) -> Self {
+        Self { n_rows, n_cols }
+    }
+    /// How many rooms the grid has.
+    pub fn len(&self) -> usize {
+        self.n_rows * self.n_cols
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The index of the room at `row`, `col`, if it's inside the grid.
+    pub fn ix(&self, row: usize, col: usize) -> Option<Ix> {
+        (row < self.n_rows && col < self.n_cols).then_some(Ix {
+            row,
+            col,
+            size: *self,
+        })
+    }
+    /// Every index in the grid, row by row.
+    pub fn indices(&self) -> impl Iterator<Item = Ix> + use<> {
+        let size = *self;
+        (0..size.n_rows).flat_map(move |row| (0..size.n_cols).map(move |col| Ix { row, col, size }))
+    }
+}
+
+impl fmt::Display for Size {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.n_rows, self.n_cols)
+    }
+}
+
+/// A room's position in a grid. It knows the grid's size, so it can tell
+/// whether it has a neighbor in each direction. Ordered row by row.
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Ix {
+    row: usize,
+    col: usize,
+    size: Size,
+}
+
+impl Ix {
+    /// The column.
+    pub fn x(&self) -> usize {
+        self.col
+    }
+    /// The row.
+    pub fn y(&self) -> usize {
+        self.row
+    }
+    pub fn size(&self) -> Size {
+        self.size
+    }
+    /// Where the room is in a row-by-row listing of the grid.
+    pub fn flat(&self) -> usize {
+        self.row * self.size.n_cols + self.col
+    }
+    pub fn north(&self) -> Option<Ix> {
+        self.row
+            .checked_sub(1)
+            .and_then(|row| self.size.ix(row, self.col))
+    }
+    pub fn east(&self) -> Option<Ix> {
+        self.size.ix(self.row, self.col + 1)
+    }
+    pub fn south(&self) -> Option<Ix> {
+        self.size.ix(self.row + 1, self.col)
+    }
+    pub fn west(&self) -> Option<Ix> {
+        self.col
+            .checked_sub(1)
+            .and_then(|col| self.size.ix(self.row, col))
+    }
+    /// The up to eight rooms surrounding this one, diagonals included.
+    pub fn surrounding(&self) -> impl Iterator<Item = Ix> + use<> {
+        let Ix { row, col, size } = *self;
+        (row.saturating_sub(1)..=row + 1)
+            .flat_map(move |r| (col.saturating_sub(1)..=col + 1).map(move |c| (r, c)))
+            .filter(move |&rc| rc != (row, col))
+            .filter_map(move |(r, c)| size.ix(r, c))
+    }
+}
+
+impl Ord for Ix {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.row, self.col).cmp(&(other.row, other.col))
+    }
+}
+
+impl PartialOrd for Ix {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Debug for Ix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+/// One bit per room of a grid, packed 64 to a word.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BitGrid {
+    size: Size,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// Every bit set to `value`.
+    pub fn new(size: Size, value: bool) -> Self {
+        let mut words = vec![if value { u64::MAX } else { 0 }; size.len().div_ceil(64)];
+        // keep the bits past the last room clear so `count` stays right
+        if value && !size.len().is_multiple_of(64) {
+            *words.last_mut().unwrap() = (1 << (size.len() % 64)) - 1;
+        }
+        Self { size, words }
+    }
+    pub fn size(&self) -> Size {
+        self.size
+    }
+    pub fn get(&self, ix: Ix) -> bool {
+        debug_assert_eq!(self.size, ix.size, "index from a different grid");
+        let i = ix.flat();
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+    pub fn set(&mut self, ix: Ix, value: bool) {
+        debug_assert_eq!(self.size, ix.size, "index from a different grid");
+        let i = ix.flat();
+        if value {
+            self.words[i / 64] |= 1 << (i % 64);
+        } else {
+            self.words[i / 64] &= !(1 << (i % 64));
+        }
+    }
+    /// How many bits are set.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_neighbors() {
+        let size = Size::new(2, 3);
+        let ix = size.ix(0, 2).unwrap();
+        assert_eq!(None, ix.north());
+        assert_eq!(None, ix.east());
+        assert_eq!(size.ix(1, 2), ix.south());
+        assert_eq!(size.ix(0, 1), ix.west());
+        assert_eq!(None, size.ix(2, 0));
+        assert_eq!(
+            vec![size.ix(0, 1), size.ix(1, 1), size.ix(1, 2)],
+            ix.surrounding().map(Some).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_indices() {
+        let size = Size::new(2, 3);
+        let ixs: Vec<Ix> = size.indices().collect();
+        assert_eq!(6, ixs.len());
+        assert!(ixs.windows(2).all(|w| w[0] < w[1]));
+        assert!(ixs.iter().enumerate().all(|(i, ix)| ix.flat() == i));
+        assert_eq!(size.ix(1, 2), ixs.last().copied());
+    }
+
+    #[test]
+    fn test_bit_grid() {
+        let size = Size::new(9, 9);
+        let mut bits = BitGrid::new(size, true);
+        assert_eq!(81, bits.count());
+        let ix = size.ix(8, 1).unwrap();
+        bits.set(ix, false);
+        assert!(!bits.get(ix));
+        assert!(bits.get(size.ix(8, 0).unwrap()));
+        assert_eq!(80, bits.count());
+        bits.set(ix, true);
+        assert_eq!(BitGrid::new(size, true), bits);
+    }
+}
@@ -1,7 +1,5 @@
-#![allow(incomplete_features)]
-#![feature(generic_const_exprs)]
-
 pub mod game;
+pub mod grid;
 pub mod maze;
 pub mod movement;
 pub mod ui;
@@ -9,7 +7,7 @@ pub mod ui;
 pub use game::game_loop;
 pub use maze::Maze;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Direction {
     North,
     South,
@@ -17,6 +15,40 @@ pub enum Direction {
     West,
 }
 
+impl Direction {
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
+        }
+    }
+    /// A quarter turn anticlockwise.
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::North => Direction::West,
+            Direction::West => Direction::South,
+            Direction::South => Direction::East,
+            Direction::East => Direction::North,
+        }
+    }
+    /// A quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        self.turn_left().opposite()
+    }
+    /// How one step this way changes the (row, column), with rows counting
+    /// down the screen.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::East => (0, 1),
+            Direction::South => (1, 0),
+            Direction::West => (0, -1),
+        }
+    }
+}
+
 impl IntoIterator for Direction {
     type Item = Self;
     type IntoIter = DirectionsIter;
@@ -68,3 +100,22 @@ impl Iterator for DirectionsIter {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        for dir in Direction::North {
+            assert_eq!(dir, dir.opposite().opposite());
+            assert_eq!(dir.opposite(), dir.turn_left().turn_left());
+            assert_eq!(dir, dir.turn_left().turn_right());
+            let (row, col) = dir.delta();
+            let (o_row, o_col) = dir.opposite().delta();
+            assert_eq!((0, 0), (row + o_row, col + o_col));
+        }
+        assert_eq!(Direction::East, Direction::North.turn_right());
+        assert_eq!((-1, 0), Direction::North.delta());
+    }
+}
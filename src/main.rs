@@ -1,7 +1,9 @@
 use color_eyre::Result;
-use samazing::*;
+use samazing::{game_loop, maze::mask::Mask};
 
 fn main() -> Result<()> {
     color_eyre::install()?;
-    game_loop::<7, 7>()
+    // an optional mask file shapes the maze; see masks/ for examples
+    let mask = std::env::args().nth(1).map(Mask::load).transpose()?;
+    game_loop(mask)
 }
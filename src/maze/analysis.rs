@@ -0,0 +1,208 @@
+use super::Maze;
+use crate::grid::Ix;
+use std::collections::{BTreeMap, BTreeSet, VecDeque, btree_map::Entry};
+
+/// Measurements of a maze's shape, for comparing generators and tuning difficulty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MazeStats {
+    pub rooms: usize,
+    /// Rooms with exactly one open door.
+    pub dead_ends: usize,
+    /// Rooms with exactly two open doors.
+    pub corridors: usize,
+    /// Rooms with three or more open doors.
+    pub junctions: usize,
+    /// Average number of ways on out of a room, not counting the way in: a
+    /// dead end has none, a corridor one, a three-way junction two.
+    pub branching_factor: f64,
+    /// Steps on the shortest path from `current_ix` to `goal`, or `None` if the
+    /// goal can't be reached.
+    pub solution_length: Option<usize>,
+    /// Junctions passed through on the shortest path, where the player has to
+    /// pick a way to go.
+    pub decision_points: Option<usize>,
+    /// Average number of rooms in an unbranching run of corridor rooms.
+    pub river: f64,
+}
+
+impl MazeStats {
+    /// Rooms on the solution path as a fraction of all rooms.
+    pub fn solution_ratio(&self) -> Option<f64> {
+        self.solution_length
+            .map(|len| (len + 1) as f64 / self.rooms as f64)
+    }
+    pub fn dead_end_ratio(&self) -> f64 {
+        self.dead_ends as f64 / self.rooms as f64
+    }
+    /// A rough 0-100 score: long solutions, frequent decisions along them and
+    /// many dead ends to wander into all make a maze harder. Unsolvable mazes
+    /// score 0.
+    pub fn difficulty(&self) -> f64 {
+        let (Some(ratio), Some(len), Some(decisions)) = (
+            self.solution_ratio(),
+            self.solution_length,
+            self.decision_points,
+        ) else {
+            return 0.0;
+        };
+        let decision_density = if len == 0 {
+            0.0
+        } else {
+            decisions as f64 / len as f64
+        };
+        100.0 * (0.5 * ratio + 0.3 * decision_density.min(1.0) + 0.2 * self.dead_end_ratio())
+    }
+}
+
+pub fn analyze(maze: &Maze) -> MazeStats {
+    let (mut dead_ends, mut corridors, mut junctions, mut ways_on) = (0, 0, 0, 0);
+    for ix in maze.size().indices() {
+        let open = maze.room(ix).open_count();
+        ways_on += open.saturating_sub(1);
+        match open {
+            0 => (),
+            1 => dead_ends += 1,
+            2 => corridors += 1,
+            _ => junctions += 1,
+        }
+    }
+    let path = shortest_path(maze, maze.current_ix, maze.goal);
+    let rooms = maze.mask.active_count();
+    MazeStats {
+        rooms,
+        dead_ends,
+        corridors,
+        junctions,
+        branching_factor: ways_on as f64 / rooms as f64,
+        solution_length: path.as_ref().map(|p| p.len() - 1),
+        decision_points: path.as_ref().map(|p| {
+            p.iter()
+                .filter(|&&ix| maze.room(ix).open_count() >= 3)
+                .count()
+        }),
+        river: river(maze),
+    }
+}
+
+/// Steps from `from` to every room reachable from it.
+pub fn distances(maze: &Maze, from: Ix) -> BTreeMap<Ix, usize> {
+    let mut dists = BTreeMap::new();
+    dists.insert(from, 0);
+    let mut queue = VecDeque::from([from]);
+    while let Some(ix) = queue.pop_front() {
+        let dist = dists[&ix];
+        for next in maze.open_neighbors(ix) {
+            if let Entry::Vacant(e) = dists.entry(next) {
+                e.insert(dist + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    dists
+}
+
+/// The rooms on a shortest route from `from` to `to`, both included.
+pub fn shortest_path(maze: &Maze, from: Ix, to: Ix) -> Option<Vec<Ix>> {
+    let mut came_from: BTreeMap<Ix, Ix> = BTreeMap::new();
+    came_from.insert(from, from);
+    let mut queue = VecDeque::from([from]);
+    while let Some(ix) = queue.pop_front() {
+        if ix == to {
+            let mut path = vec![to];
+            let mut curr = to;
+            while curr != from {
+                curr = came_from[&curr];
+                path.push(curr);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for next in maze.open_neighbors(ix) {
+            if let Entry::Vacant(e) = came_from.entry(next) {
+                e.insert(ix);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+fn river(maze: &Maze) -> f64 {
+    let is_corridor = |ix: Ix| maze.room(ix).open_count() == 2;
+    let mut seen: BTreeSet<Ix> = BTreeSet::new();
+    let (mut runs, mut total) = (0, 0);
+    for start in maze.size().indices() {
+        if !is_corridor(start) || !seen.insert(start) {
+            continue;
+        }
+        runs += 1;
+        let mut stack = vec![start];
+        while let Some(ix) = stack.pop() {
+            total += 1;
+            for next in maze.open_neighbors(ix) {
+                if is_corridor(next) && seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+    }
+    if runs == 0 {
+        0.0
+    } else {
+        total as f64 / runs as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Direction, grid::Size};
+
+    #[test]
+    fn test_analyze_corridor() {
+        let mut m = Maze::new(1, 4);
+        for col in 0..3 {
+            m.open(m.size().ix(0, col).unwrap(), Direction::East);
+        }
+        let stats = analyze(&m);
+        assert_eq!(2, stats.dead_ends);
+        assert_eq!(2, stats.corridors);
+        assert_eq!(0, stats.junctions);
+        assert_eq!(0.5, stats.branching_factor);
+        assert_eq!(Some(3), stats.solution_length);
+        assert_eq!(Some(0), stats.decision_points);
+        assert_eq!(Some(1.0), stats.solution_ratio());
+        assert_eq!(2.0, stats.river);
+    }
+
+    #[test]
+    fn test_analyze_junction() {
+        // a T: the top row is open, with a spur down from the middle
+        let mut m = Maze::new(2, 3);
+        let ix = |row, col| Size::new(2, 3).ix(row, col).unwrap();
+        m.open(ix(0, 0), Direction::East);
+        m.open(ix(0, 1), Direction::East);
+        m.open(ix(0, 1), Direction::South);
+        m.open(ix(1, 1), Direction::East);
+        let stats = analyze(&m);
+        assert_eq!(3, stats.dead_ends);
+        assert_eq!(1, stats.corridors);
+        assert_eq!(1, stats.junctions);
+        assert_eq!(Some(3), stats.solution_length);
+        assert_eq!(Some(1), stats.decision_points);
+        assert_eq!(
+            Some(vec![ix(0, 0), ix(0, 1), ix(1, 1), ix(1, 2)]),
+            shortest_path(&m, m.current_ix, m.goal)
+        );
+        assert!(stats.difficulty() > 0.0);
+    }
+
+    #[test]
+    fn test_analyze_unsolvable() {
+        let m = Maze::new(3, 3);
+        let stats = analyze(&m);
+        assert_eq!(None, stats.solution_length);
+        assert_eq!(0.0, stats.difficulty());
+        assert_eq!(1, distances(&m, m.current_ix).len());
+    }
+}
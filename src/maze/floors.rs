@@ -0,0 +1,105 @@
+use super::{Maze, MoveError, MoveResult};
+use crate::grid::Ix;
+use std::collections::BTreeSet;
+
+/// Which floor a room is on, counting up from 0, and where on that floor.
+pub type FloorPos = (usize, Ix);
+
+/// A stack of square mazes, all the same shape, joined by stairs. The player
+/// starts where the bottom floor starts them, and the goal is the top floor's.
+#[derive(Debug, Clone)]
+pub struct Floors {
+    pub floors: Vec<Maze>,
+    /// Rooms with stairs up to the same room on the floor above.
+    stairs: BTreeSet<FloorPos>,
+    /// The floor the player is on. Where they are on it is that floor's
+    /// `current_ix`.
+    pub floor: usize,
+    pub goal: FloorPos,
+}
+
+impl Floors {
+    pub fn new(floors: Vec<Maze>) -> Self {
+        let top = floors.len() - 1;
+        Self {
+            goal: (top, floors[top].goal),
+            floors,
+            stairs: BTreeSet::new(),
+            floor: 0,
+        }
+    }
+    pub fn current(&self) -> FloorPos {
+        (self.floor, self.floors[self.floor].current_ix)
+    }
+    /// The maze the player is on.
+    pub fn here(&mut self) -> &mut Maze {
+        &mut self.floors[self.floor]
+    }
+    /// Puts stairs between `ix` on `floor` and `ix` on the floor above.
+    pub fn add_stairs(&mut self, floor: usize, ix: Ix) {
+        if floor + 1 < self.floors.len() {
+            self.stairs.insert((floor, ix));
+        }
+    }
+    pub fn has_stairs_up(&self, (floor, ix): FloorPos) -> bool {
+        self.stairs.contains(&(floor, ix))
+    }
+    pub fn has_stairs_down(&self, (floor, ix): FloorPos) -> bool {
+        floor > 0 && self.stairs.contains(&(floor - 1, ix))
+    }
+    /// Takes the stairs up, or down, out of the player's room.
+    pub fn climb(&mut self, up: bool) -> MoveResult {
+        let (floor, ix) = self.current();
+        let next = match up {
+            true if self.has_stairs_up((floor, ix)) => floor + 1,
+            false if self.has_stairs_down((floor, ix)) => floor - 1,
+            _ => return Err(MoveError::NoStairs),
+        };
+        self.floor = next;
+        self.floors[next].current_ix = ix;
+        Ok(ix)
+    }
+    /// The rooms reachable from `pos` through a single open door or flight of
+    /// stairs.
+    pub fn open_neighbors(&self, pos: FloorPos) -> Vec<FloorPos> {
+        let (floor, ix) = pos;
+        let mut next: Vec<FloorPos> = self.floors[floor]
+            .open_neighbors(ix)
+            .map(|i| (floor, i))
+            .collect();
+        if self.has_stairs_up(pos) {
+            next.push((floor + 1, ix));
+        }
+        if self.has_stairs_down(pos) {
+            next.push((floor - 1, ix));
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Direction;
+
+    #[test]
+    fn test_climb() {
+        let mut floors = Floors::new(vec![Maze::new(2, 2), Maze::new(2, 2)]);
+        let size = floors.floors[0].size();
+        let ix = |row, col| size.ix(row, col).unwrap();
+        floors.here().open(ix(0, 0), Direction::East);
+        floors.add_stairs(0, ix(0, 1));
+        // there's no floor above the top one
+        floors.add_stairs(1, ix(0, 1));
+        assert_eq!(Err(MoveError::NoStairs), floors.climb(true));
+        assert_eq!(Ok(ix(0, 1)), floors.here().try_move(Direction::East));
+        assert_eq!(Err(MoveError::NoStairs), floors.climb(false));
+        assert_eq!(Ok(ix(0, 1)), floors.climb(true));
+        assert_eq!((1, ix(0, 1)), floors.current());
+        assert!(floors.has_stairs_down(floors.current()));
+        assert_eq!(Err(MoveError::NoStairs), floors.climb(true));
+        assert_eq!(vec![(0, ix(0, 1))], floors.open_neighbors(floors.current()));
+        assert_eq!(Ok(ix(0, 1)), floors.climb(false));
+        assert_eq!(0, floors.floor);
+    }
+}
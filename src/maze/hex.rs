@@ -0,0 +1,241 @@
+use super::{DoorState, MoveError, MoveResult, topology::Topology};
+use crate::grid::{BitGrid, Ix, Size};
+
+/// The six ways out of a flat-topped hexagonal room.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum HexDirection {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+impl HexDirection {
+    /// Clockwise from north.
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::North,
+        HexDirection::NorthEast,
+        HexDirection::SouthEast,
+        HexDirection::South,
+        HexDirection::SouthWest,
+        HexDirection::NorthWest,
+    ];
+
+    pub fn opposite(self) -> Self {
+        match self {
+            HexDirection::North => HexDirection::South,
+            HexDirection::NorthEast => HexDirection::SouthWest,
+            HexDirection::SouthEast => HexDirection::NorthWest,
+            HexDirection::South => HexDirection::North,
+            HexDirection::SouthWest => HexDirection::NorthEast,
+            HexDirection::NorthWest => HexDirection::SouthEast,
+        }
+    }
+}
+
+/// The room next to `ix` in direction `dir`, if it's on the grid. Rooms are laid
+/// out in columns, with every odd column sitting half a room lower than the
+/// columns either side of it.
+pub fn hex_neighbor(ix: Ix, dir: HexDirection) -> Option<Ix> {
+    let (row, col) = (ix.y(), ix.x());
+    let odd = col % 2 == 1;
+    // rows of the diagonal neighbors, to the north and to the south
+    let (up, down) = if odd {
+        (Some(row), row.checked_add(1))
+    } else {
+        (row.checked_sub(1), Some(row))
+    };
+    let (row, col) = match dir {
+        HexDirection::North => (row.checked_sub(1), Some(col)),
+        HexDirection::NorthEast => (up, col.checked_add(1)),
+        HexDirection::SouthEast => (down, col.checked_add(1)),
+        HexDirection::South => (row.checked_add(1), Some(col)),
+        HexDirection::SouthWest => (down, col.checked_sub(1)),
+        HexDirection::NorthWest => (up, col.checked_sub(1)),
+    };
+    ix.size().ix(row?, col?)
+}
+
+/// A maze of hexagonal rooms. Like `Maze`, each wall is stored once, as a bit
+/// on the room to its north or west.
+#[derive(Debug, Clone)]
+pub struct HexMaze {
+    /// Set for every room whose south door is open.
+    open_south: BitGrid,
+    /// Set for every room whose south-east door is open.
+    open_south_east: BitGrid,
+    /// Set for every room whose north-east door is open.
+    open_north_east: BitGrid,
+    pub current_ix: Ix,
+    pub goal: Ix,
+}
+
+impl HexMaze {
+    pub fn new(size: Size) -> Self {
+        Self {
+            open_south: BitGrid::new(size, false),
+            open_south_east: BitGrid::new(size, false),
+            open_north_east: BitGrid::new(size, false),
+            current_ix: size.ix(0, 0).unwrap(),
+            goal: size.ix(size.n_rows - 1, size.n_cols - 1).unwrap(),
+        }
+    }
+    pub fn size(&self) -> Size {
+        self.open_south.size()
+    }
+    /// The wall on the `dir` side of `ix`, as the room it's stored on and which
+    /// of that room's walls it is. `None` if there's no door there.
+    fn wall(&self, ix: Ix, dir: HexDirection) -> Option<(Ix, HexDirection)> {
+        let next = hex_neighbor(ix, dir)?;
+        Some(match dir {
+            HexDirection::South | HexDirection::SouthEast | HexDirection::NorthEast => (ix, dir),
+            _ => (next, dir.opposite()),
+        })
+    }
+    fn bits(&mut self, side: HexDirection) -> &mut BitGrid {
+        match side {
+            HexDirection::South => &mut self.open_south,
+            HexDirection::SouthEast => &mut self.open_south_east,
+            _ => &mut self.open_north_east,
+        }
+    }
+    /// The door on the `dir` side of `ix`, if there is one.
+    pub fn door(&self, ix: Ix, dir: HexDirection) -> Option<DoorState> {
+        let (at, side) = self.wall(ix, dir)?;
+        let bits = match side {
+            HexDirection::South => &self.open_south,
+            HexDirection::SouthEast => &self.open_south_east,
+            _ => &self.open_north_east,
+        };
+        Some(if bits.get(at) {
+            DoorState::Open
+        } else {
+            DoorState::Closed
+        })
+    }
+    pub fn doors(&self, ix: Ix) -> [Option<DoorState>; 6] {
+        HexDirection::ALL.map(|dir| self.door(ix, dir))
+    }
+    pub fn open(&mut self, ix: Ix, dir: HexDirection) {
+        if let Some((at, side)) = self.wall(ix, dir) {
+            self.bits(side).set(at, true);
+        }
+    }
+    pub fn close(&mut self, ix: Ix, dir: HexDirection) {
+        if let Some((at, side)) = self.wall(ix, dir) {
+            self.bits(side).set(at, false);
+        }
+    }
+    /// Moves the player one room `dir`, or says why they can't go that way.
+    pub fn try_move(&mut self, dir: HexDirection) -> MoveResult {
+        let next = hex_neighbor(self.current_ix, dir).ok_or(MoveError::Edge)?;
+        match self.door(self.current_ix, dir) {
+            Some(DoorState::Open) => {
+                self.current_ix = next;
+                Ok(next)
+            }
+            _ => Err(MoveError::Closed),
+        }
+    }
+    /// The rooms reachable from `ix` through a single open door.
+    pub fn open_neighbors(&self, ix: Ix) -> impl Iterator<Item = Ix> + use<'_> {
+        HexDirection::ALL
+            .into_iter()
+            .filter(move |&dir| self.door(ix, dir) == Some(DoorState::Open))
+            .filter_map(move |dir| hex_neighbor(ix, dir))
+    }
+    pub fn open_door_count(&self) -> usize {
+        self.open_south.count() + self.open_south_east.count() + self.open_north_east.count()
+    }
+    pub fn is_done(&self) -> bool {
+        self.current_ix == self.goal
+    }
+}
+
+impl Topology for HexMaze {
+    type Dir = HexDirection;
+
+    fn start(&self) -> Ix {
+        self.current_ix
+    }
+    fn rooms(&self) -> impl Iterator<Item = Ix> {
+        self.size().indices()
+    }
+    fn exits(&self, ix: Ix) -> Vec<(HexDirection, Ix)> {
+        HexDirection::ALL
+            .into_iter()
+            .filter_map(|dir| hex_neighbor(ix, dir).map(|next| (dir, next)))
+            .collect()
+    }
+    fn open(&mut self, ix: Ix, dir: HexDirection) {
+        HexMaze::open(self, ix, dir);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hex_neighbors() {
+        let size = Size::new(3, 3);
+        let ix = |row, col| size.ix(row, col);
+        // even column: the diagonals lean north
+        let even = ix(1, 0).unwrap();
+        assert_eq!(ix(0, 0), hex_neighbor(even, HexDirection::North));
+        assert_eq!(ix(0, 1), hex_neighbor(even, HexDirection::NorthEast));
+        assert_eq!(ix(1, 1), hex_neighbor(even, HexDirection::SouthEast));
+        assert_eq!(None, hex_neighbor(even, HexDirection::SouthWest));
+        // odd column: the diagonals lean south
+        let odd = ix(1, 1).unwrap();
+        assert_eq!(ix(1, 2), hex_neighbor(odd, HexDirection::NorthEast));
+        assert_eq!(ix(2, 2), hex_neighbor(odd, HexDirection::SouthEast));
+        assert_eq!(ix(2, 0), hex_neighbor(odd, HexDirection::SouthWest));
+        assert_eq!(ix(1, 0), hex_neighbor(odd, HexDirection::NorthWest));
+        // every neighbor leads back the opposite way
+        for ix in size.indices() {
+            for dir in HexDirection::ALL {
+                if let Some(next) = hex_neighbor(ix, dir) {
+                    assert_eq!(
+                        Some(ix),
+                        hex_neighbor(next, dir.opposite()),
+                        "{ix:?} {dir:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_doors() {
+        let size = Size::new(3, 3);
+        let mut m = HexMaze::new(size);
+        let ix = |row, col| size.ix(row, col).unwrap();
+        assert_eq!(
+            [
+                Some(DoorState::Closed),
+                Some(DoorState::Closed),
+                Some(DoorState::Closed),
+                Some(DoorState::Closed),
+                Some(DoorState::Closed),
+                Some(DoorState::Closed),
+            ],
+            m.doors(ix(1, 1))
+        );
+        assert_eq!(None, m.door(ix(0, 0), HexDirection::NorthEast));
+        m.open(ix(1, 1), HexDirection::NorthWest);
+        assert_eq!(
+            Some(DoorState::Open),
+            m.door(ix(1, 0), HexDirection::SouthEast)
+        );
+        m.open(ix(0, 0), HexDirection::South);
+        assert_eq!(1, m.open_neighbors(ix(1, 1)).count());
+        assert_eq!(2, m.open_door_count());
+        assert_eq!(Err(MoveError::Edge), m.try_move(HexDirection::North));
+        assert_eq!(Err(MoveError::Closed), m.try_move(HexDirection::SouthEast));
+        assert_eq!(Ok(ix(1, 0)), m.try_move(HexDirection::South));
+        assert_eq!(Ok(ix(1, 1)), m.try_move(HexDirection::SouthEast));
+    }
+}
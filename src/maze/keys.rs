@@ -0,0 +1,209 @@
+use super::{DoorState, Maze, analysis::shortest_path};
+use crate::{Direction, grid::Ix};
+use rand::{
+    Rng,
+    seq::{IndexedRandom, index},
+};
+use std::collections::BTreeSet;
+
+/// Which key opens a locked door. Keys are numbered from 0.
+pub type KeyId = usize;
+
+impl Maze {
+    /// The rooms reachable from `from` through open doors, and through locked
+    /// doors whose key is in `keys`.
+    pub fn reachable(&self, from: Ix, keys: &BTreeSet<KeyId>) -> BTreeSet<Ix> {
+        self.reachable_through(from, keys, DoorState::is_passable)
+    }
+    /// Like `reachable`, but through unlocked doors that `passable` says can
+    /// be passed from this side.
+    pub(super) fn reachable_through(
+        &self,
+        from: Ix,
+        keys: &BTreeSet<KeyId>,
+        passable: impl Fn(DoorState) -> bool,
+    ) -> BTreeSet<Ix> {
+        let mut seen = BTreeSet::from([from]);
+        let mut stack = vec![from];
+        while let Some(ix) = stack.pop() {
+            for (dir, st) in &self.room(ix).doors {
+                let passable = match st {
+                    DoorState::Locked(key) => keys.contains(&key),
+                    st => passable(st),
+                };
+                if passable
+                    && let Some(next) = self.through(ix, dir)
+                    && seen.insert(next)
+                {
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+    /// Whether there's a locked door, or a key lying about or held.
+    pub fn has_keys(&self) -> bool {
+        !self.locks.is_empty() || !self.keys.is_empty() || !self.held.is_empty()
+    }
+    /// The rooms the player can get to from where they are, picking up every
+    /// key they come across on the way, and the keys they'd have by then.
+    pub fn reachable_collecting_keys(&self) -> (BTreeSet<Ix>, BTreeSet<KeyId>) {
+        let mut keys = self.held.clone();
+        loop {
+            let rooms = self.reachable(self.current_ix, &keys);
+            let found = rooms
+                .iter()
+                .filter_map(|ix| self.keys.get(ix))
+                .filter(|key| !keys.contains(key))
+                .count();
+            if found == 0 {
+                return (rooms, keys);
+            }
+            keys.extend(rooms.iter().filter_map(|ix| self.keys.get(ix)));
+        }
+    }
+}
+
+/// Locks up to `count` doors on the way from `current_ix` to `goal`, and drops
+/// each key somewhere the player can get to before they meet its lock. Run it
+/// after placement, on a maze with no locks yet. A door with nowhere to put its
+/// key is left open, so the keys are numbered from 0 without gaps. Returns how
+/// many doors were locked.
+pub fn lock_doors(maze: &mut Maze, rng: &mut impl Rng, count: usize) -> usize {
+    let Some(path) = shortest_path(maze, maze.current_ix, maze.goal) else {
+        return 0;
+    };
+    // the doors along the solution, in the order the player meets them
+    let steps: Vec<(Ix, Direction)> = path
+        .windows(2)
+        .map(|w| {
+            let dir = Direction::North
+                .into_iter()
+                .find(|&dir| maze.through(w[0], dir) == Some(w[1]))
+                .unwrap();
+            (w[0], dir)
+        })
+        .collect();
+    let mut picked = index::sample(rng, steps.len(), count.min(steps.len())).into_vec();
+    picked.sort();
+    // locked with stand-in keys for now, so they block the way while looking
+    // for somewhere to put each key; the later ones are never below `locked`
+    for (key, &step) in picked.iter().enumerate() {
+        let (ix, dir) = steps[step];
+        maze.lock(ix, dir, key);
+    }
+    let mut locked = 0;
+    for &step in &picked {
+        // every earlier key can be had by now, and every later lock is still
+        // further along the solution, so this is all within reach
+        let before: BTreeSet<KeyId> = (0..locked).collect();
+        let spots: Vec<Ix> = maze
+            .reachable(maze.current_ix, &before)
+            .into_iter()
+            .filter(|ix| *ix != maze.current_ix && *ix != maze.goal && !maze.keys.contains_key(ix))
+            .collect();
+        let (ix, dir) = steps[step];
+        match spots.choose(rng) {
+            Some(&spot) => {
+                maze.lock(ix, dir, locked);
+                maze.keys.insert(spot, locked);
+                locked += 1;
+            }
+            None => maze.open(ix, dir),
+        }
+    }
+    locked
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        game::{Generator, Seeder},
+        grid::Size,
+        maze::{MoveError, placement::Placement},
+    };
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn corridor() -> Maze {
+        let mut m = Maze::new(1, 4);
+        for col in 0..3 {
+            m.open(m.size().ix(0, col).unwrap(), Direction::East);
+        }
+        m
+    }
+
+    #[test]
+    fn test_locked_door() {
+        let mut m = corridor();
+        let ix = |col| Size::new(1, 4).ix(0, col).unwrap();
+        m.lock(ix(1), Direction::East, 0);
+        assert_eq!(Some(DoorState::Locked(0)), m.door(ix(2), Direction::West));
+        m.keys.insert(ix(1), 0);
+        assert_eq!(Some(0), m.room(ix(1)).key);
+        assert_eq!(Ok(ix(1)), m.try_move(Direction::East));
+        assert!(m.held.contains(&0));
+        assert_eq!(None, m.room(ix(1)).key);
+        assert_eq!(Ok(ix(2)), m.try_move(Direction::East));
+        // and it stays open
+        assert_eq!(Some(DoorState::Open), m.door(ix(1), Direction::East));
+    }
+
+    #[test]
+    fn test_key_behind_its_own_lock() {
+        let mut m = corridor();
+        let ix = |col| Size::new(1, 4).ix(0, col).unwrap();
+        m.lock(ix(1), Direction::East, 0);
+        m.keys.insert(ix(3), 0);
+        assert_eq!(Ok(ix(1)), m.try_move(Direction::East));
+        assert_eq!(Err(MoveError::Locked(0)), m.try_move(Direction::East));
+        assert!(m.validate().is_err());
+        m.keys.insert(ix(0), 0);
+        m.keys.remove(&ix(3));
+        assert_eq!(Ok(()), m.validate());
+    }
+
+    #[test]
+    fn test_lock_doors_solvable() {
+        for seed in 0..200 {
+            for generator in [
+                Generator::Backtrack,
+                Generator::Kruskal,
+                Generator::Division,
+            ] {
+                let mut rng = StdRng::seed_from_u64(seed);
+                let mut m = Maze::new(7, 7);
+                generator.seed(&mut m, &mut rng);
+                m.place(Placement::FarthestPair, &mut rng);
+                let locked = lock_doors(&mut m, &mut rng, 3);
+                assert!(locked > 0, "seed {seed} {generator:?}: nothing locked");
+                assert_eq!(locked, m.keys.len());
+                assert!(!m.keys.contains_key(&m.current_ix));
+                assert_eq!(Ok(()), m.validate(), "seed {seed} {generator:?}");
+                let ids: BTreeSet<KeyId> = m.keys.values().copied().collect();
+                assert_eq!((0..locked).collect::<BTreeSet<_>>(), ids);
+                // without the keys the goal is out of reach
+                assert!(
+                    !m.reachable(m.current_ix, &BTreeSet::new())
+                        .contains(&m.goal)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lock_doors_nowhere_for_key() {
+        let mut m = corridor();
+        let ix = |col| Size::new(1, 4).ix(0, col).unwrap();
+        m.current_ix = ix(0);
+        m.goal = ix(3);
+        // the first door has only the start room before it, so it stays open
+        // and the next two get keys 0 and 1
+        assert_eq!(2, lock_doors(&mut m, &mut StdRng::seed_from_u64(0), 3));
+        assert_eq!(Some(DoorState::Open), m.door(ix(0), Direction::East));
+        assert_eq!(Some(DoorState::Locked(0)), m.door(ix(1), Direction::East));
+        assert_eq!(Some(DoorState::Locked(1)), m.door(ix(2), Direction::East));
+        assert_eq!(Some(&0), m.keys.get(&ix(1)));
+        assert_eq!(Some(&1), m.keys.get(&ix(2)));
+    }
+}
@@ -0,0 +1,162 @@
+use crate::grid::{BitGrid, Ix, Size};
+use std::{collections::BTreeSet, fmt, fs, io, path::Path};
+
+/// Which rooms of the grid exist. Masked-out rooms have no doors, and no doors
+/// lead into them, so mazes can take the shape of whatever the active rooms draw.
+#[derive(Debug, Clone)]
+pub struct Mask {
+    active: BitGrid,
+}
+
+#[derive(Debug)]
+pub enum MaskError {
+    Io(io::Error),
+    /// This row (counting from 0) isn't as long as the first one.
+    RowLength(usize),
+    /// A character other than `#` or `.` at this row and column.
+    BadChar(usize, usize, char),
+    NoActiveRooms,
+    /// The active rooms form more than one island.
+    Disconnected,
+}
+
+impl fmt::Display for MaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaskError::Io(e) => write!(f, "couldn't read mask: {e}"),
+            MaskError::RowLength(row) => {
+                write!(f, "mask row {row} isn't the same length as the first")
+            }
+            MaskError::BadChar(row, col, c) => {
+                write!(
+                    f,
+                    "mask has {c:?} at row {row}, column {col}; use '#' or '.'"
+                )
+            }
+            MaskError::NoActiveRooms => write!(f, "mask has no active rooms"),
+            MaskError::Disconnected => write!(f, "mask's active rooms aren't all connected"),
+        }
+    }
+}
+
+impl std::error::Error for MaskError {}
+
+impl From<io::Error> for MaskError {
+    fn from(e: io::Error) -> Self {
+        MaskError::Io(e)
+    }
+}
+
+impl Mask {
+    /// Every room of a `size` grid active.
+    pub fn full(size: Size) -> Self {
+        Self {
+            active: BitGrid::new(size, true),
+        }
+    }
+    /// Reads ASCII art with one line per row, where `#` is an active room and
+    /// `.` a masked-out one. The art sets the size of the maze. Indentation and
+    /// blank lines before and after are ignored.
+    pub fn parse(text: &str) -> Result<Self, MaskError> {
+        let lines: Vec<&str> = text.trim().lines().map(str::trim).collect();
+        let n_cols = lines.first().map_or(0, |line| line.chars().count());
+        let size = Size::new(lines.len(), n_cols);
+        let mut active = BitGrid::new(size, false);
+        for (row, line) in lines.iter().enumerate() {
+            if line.chars().count() != n_cols {
+                return Err(MaskError::RowLength(row));
+            }
+            for (col, c) in line.chars().enumerate() {
+                match c {
+                    '#' => active.set(size.ix(row, col).unwrap(), true),
+                    '.' => (),
+                    c => return Err(MaskError::BadChar(row, col, c)),
+                }
+            }
+        }
+        if active.count() == 0 {
+            return Err(MaskError::NoActiveRooms);
+        }
+        let mask = Self { active };
+        if !mask.is_connected() {
+            return Err(MaskError::Disconnected);
+        }
+        Ok(mask)
+    }
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MaskError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+    pub fn size(&self) -> Size {
+        self.active.size()
+    }
+    pub fn is_active(&self, ix: Ix) -> bool {
+        self.active.get(ix)
+    }
+    pub fn set_active(&mut self, ix: Ix, active: bool) {
+        self.active.set(ix, active);
+    }
+    pub fn active_rooms(&self) -> impl Iterator<Item = Ix> {
+        self.size().indices().filter(|&ix| self.active.get(ix))
+    }
+    pub fn active_count(&self) -> usize {
+        self.active.count()
+    }
+    pub fn is_full(&self) -> bool {
+        self.active_count() == self.size().len()
+    }
+    /// Whether every active room can be reached from every other one without
+    /// leaving the mask. Generators need this to produce a single maze.
+    pub fn is_connected(&self) -> bool {
+        let Some(start) = self.active_rooms().next() else {
+            return true;
+        };
+        let mut seen = BTreeSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(ix) = stack.pop() {
+            for next in [ix.north(), ix.east(), ix.south(), ix.west()]
+                .into_iter()
+                .flatten()
+            {
+                if self.active.get(next) && seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen.len() == self.active_count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let mask = Mask::parse("\n.#.\n###\n").unwrap();
+        assert_eq!(Size::new(2, 3), mask.size());
+        assert!(!mask.is_active(mask.size().ix(0, 0).unwrap()));
+        assert!(mask.is_active(mask.size().ix(0, 1).unwrap()));
+        assert_eq!(4, mask.active_count());
+        assert!(!mask.is_full());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            Mask::parse("###\n##"),
+            Err(MaskError::RowLength(1))
+        ));
+        assert!(matches!(
+            Mask::parse("###\n#x#"),
+            Err(MaskError::BadChar(1, 1, 'x'))
+        ));
+        assert!(matches!(
+            Mask::parse("...\n..."),
+            Err(MaskError::NoActiveRooms)
+        ));
+        assert!(matches!(
+            Mask::parse("#.#\n#.#"),
+            Err(MaskError::Disconnected)
+        ));
+    }
+}
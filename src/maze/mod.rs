@@ -0,0 +1,710 @@
+use crate::{
+    Direction, DirectionsIter,
+    grid::{BitGrid, Ix, Size},
+};
+use keys::KeyId;
+use mask::Mask;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+use weave::RoomKind;
+use wrap::Wrap;
+
+pub mod analysis;
+pub mod floors;
+pub mod hex;
+pub mod keys;
+pub mod mask;
+pub mod placement;
+pub mod polar;
+pub mod topology;
+pub mod validation;
+pub mod weave;
+pub mod wrap;
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum DoorState {
+    Open,
+    Closed,
+    /// Shut until the player passes through holding this key, then open for good.
+    Locked(KeyId),
+    /// Open, but only for going out of this room.
+    OneWayOut,
+    /// Open, but only for coming into this room.
+    OneWayIn,
+}
+
+impl DoorState {
+    /// Whether there's a gap in the wall, whichever way it can be passed.
+    pub fn is_open(self) -> bool {
+        matches!(
+            self,
+            DoorState::Open | DoorState::OneWayOut | DoorState::OneWayIn
+        )
+    }
+    /// Whether the player can go through from this side without a key.
+    pub fn is_passable(self) -> bool {
+        matches!(self, DoorState::Open | DoorState::OneWayOut)
+    }
+}
+
+/// The doors of one room, as seen from inside it. `None` means there's no door
+/// on that side: it's the edge of the grid or a masked-out room.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Doors {
+    pub north: Option<DoorState>,
+    pub east: Option<DoorState>,
+    pub south: Option<DoorState>,
+    pub west: Option<DoorState>,
+}
+
+impl Doors {
+    pub fn any_open(&self) -> bool {
+        for (_, st) in self {
+            if st.is_open() {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl Default for Doors {
+    fn default() -> Self {
+        Self {
+            north: Some(DoorState::Closed),
+            east: Some(DoorState::Closed),
+            south: Some(DoorState::Closed),
+            west: Some(DoorState::Closed),
+        }
+    }
+}
+
+impl IntoIterator for &Doors {
+    type Item = (Direction, DoorState);
+    type IntoIter = DoorsIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        DoorsIter::new(*self)
+    }
+}
+
+pub struct DoorsIter {
+    dirs: DirectionsIter,
+    doors: Doors,
+}
+
+impl DoorsIter {
+    fn new(doors: Doors) -> Self {
+        Self {
+            dirs: DirectionsIter::new(),
+            doors,
+        }
+    }
+}
+
+impl Iterator for DoorsIter {
+    type Item = (Direction, DoorState);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for dir in self.dirs.by_ref() {
+            if let Some(ds) = match dir {
+                Direction::North => self.doors.north,
+                Direction::South => self.doors.south,
+                Direction::East => self.doors.east,
+                Direction::West => self.doors.west,
+            } {
+                return Some((dir, ds));
+            }
+        }
+        None
+    }
+}
+
+/// Why the player couldn't move, from `Maze::try_move`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MoveError {
+    /// The door that way is shut.
+    Closed,
+    /// That way is off the edge of the grid.
+    Edge,
+    /// That way is a room the mask leaves out.
+    NoRoom,
+    /// The door that way needs a key the player hasn't picked up.
+    Locked(KeyId),
+    /// The door that way only opens from the other side.
+    OneWay,
+    /// There are no stairs that way out of the room.
+    NoStairs,
+    /// That way is the corridor passing under the crossing the player is on.
+    Underneath,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MoveError::Closed => write!(f, "that door is closed"),
+            MoveError::Edge => write!(f, "that's the edge of the maze"),
+            MoveError::NoRoom => write!(f, "there's no room that way"),
+            MoveError::Locked(key) => write!(f, "that door needs key {key}"),
+            MoveError::OneWay => write!(f, "that door only opens from the other side"),
+            MoveError::NoStairs => write!(f, "there are no stairs that way here"),
+            MoveError::Underneath => write!(f, "that corridor runs underneath you"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// The room the player moved into, or why they couldn't.
+pub type MoveResult = Result<Ix, MoveError>;
+
+/// A read-only view of one room, made on demand by `Maze::room`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Room {
+    pub row: usize,
+    pub col: usize,
+    pub doors: Doors,
+    /// A key lying in the room, waiting to be picked up.
+    pub key: Option<KeyId>,
+    /// Which doors lead round to the other edge of the grid, indexed like
+    /// `Direction`.
+    pub wrapped: [bool; 4],
+    pub kind: RoomKind,
+}
+
+impl Room {
+    pub fn description(&self) -> String {
+        format!("room ({}, {})", self.row, self.col)
+    }
+    pub fn all_doors(&self) -> DoorsIter {
+        DoorsIter::new(self.doors)
+    }
+    pub fn none_open(&self) -> bool {
+        !self.doors.any_open()
+    }
+    pub fn available_directions(&self) -> impl Iterator<Item = Direction> + use<> {
+        self.all_doors().map(|(dir, _)| dir)
+    }
+    pub fn open_count(&self) -> usize {
+        self.all_doors().filter(|&(_, st)| st.is_open()).count()
+    }
+    /// Whether the door on the `dir` side leads round to the other edge.
+    pub fn wraps(&self, dir: Direction) -> bool {
+        self.wrapped[dir as usize]
+    }
+}
+
+/// The room next to `ix` in direction `dir`, if it's on the grid. Mazes whose
+/// edges wrap use `Maze::neighbor` instead.
+pub fn neighbor(ix: Ix, dir: Direction) -> Option<Ix> {
+    match dir {
+        Direction::North => ix.north(),
+        Direction::East => ix.east(),
+        Direction::South => ix.south(),
+        Direction::West => ix.west(),
+    }
+}
+
+/// Each wall between two rooms is stored once, as a bit on the room to its west
+/// or north, so the two sides of a door can't disagree. `Room`s are built from
+/// the bits when asked for.
+#[derive(Debug, Clone)]
+pub struct Maze {
+    /// Set for every room whose east door is open.
+    open_east: BitGrid,
+    /// Set for every room whose south door is open.
+    open_south: BitGrid,
+    /// The closed doors that a key opens, keyed like `wall`.
+    locks: BTreeMap<(Ix, Direction), KeyId>,
+    /// The open doors that can only be passed going one way, keyed like `wall`.
+    one_way: BTreeMap<(Ix, Direction), Direction>,
+    /// Rooms where one corridor passes under another.
+    crossings: BTreeMap<Ix, RoomKind>,
+    pub current_ix: Ix,
+    pub goal: Ix,
+    pub mask: Mask,
+    /// Which edges lead round to the other side. Set it before seeding.
+    pub wrap: Wrap,
+    /// Keys lying in rooms.
+    pub keys: BTreeMap<Ix, KeyId>,
+    /// Keys the player has picked up.
+    pub held: BTreeSet<KeyId>,
+}
+
+impl Maze {
+    pub fn new(n_rows: usize, n_cols: usize) -> Self {
+        Self::with_mask(Mask::full(Size::new(n_rows, n_cols)))
+    }
+    /// A maze made of only the rooms `mask` leaves active. Masked-out rooms
+    /// have no doors, and neither do the walls facing them, so seeders and
+    /// players treat them like the edge of the grid.
+    pub fn with_mask(mask: Mask) -> Self {
+        let mut active = mask.active_rooms();
+        let first = active.next().unwrap_or(mask.size().ix(0, 0).unwrap());
+        let last = active.last().unwrap_or(first);
+        Self {
+            open_east: BitGrid::new(mask.size(), false),
+            open_south: BitGrid::new(mask.size(), false),
+            locks: BTreeMap::new(),
+            one_way: BTreeMap::new(),
+            crossings: BTreeMap::new(),
+            current_ix: first,
+            goal: last,
+            mask,
+            wrap: Wrap::None,
+            keys: BTreeMap::new(),
+            held: BTreeSet::new(),
+        }
+    }
+    pub fn size(&self) -> Size {
+        self.mask.size()
+    }
+    pub fn n_rows(&self) -> usize {
+        self.size().n_rows
+    }
+    pub fn n_cols(&self) -> usize {
+        self.size().n_cols
+    }
+    pub fn is_active(&self, ix: Ix) -> bool {
+        self.mask.is_active(ix)
+    }
+    /// The room next to `ix` in direction `dir`, going round the edges if the
+    /// maze wraps. Masked-out rooms count.
+    pub fn neighbor(&self, ix: Ix, dir: Direction) -> Option<Ix> {
+        let (d_row, d_col) = dir.delta();
+        self.wrap.resolve(
+            self.size(),
+            ix.y() as isize + d_row,
+            ix.x() as isize + d_col,
+        )
+    }
+    /// Whether going `dir` from `ix` crosses an edge of the grid to the other side.
+    pub fn wraps(&self, ix: Ix, dir: Direction) -> bool {
+        neighbor(ix, dir).is_none() && self.neighbor(ix, dir).is_some()
+    }
+    /// The door on the `dir` side of `ix`, if there is one.
+    pub fn door(&self, ix: Ix, dir: Direction) -> Option<DoorState> {
+        let wall = self.wall(ix, dir)?;
+        Some(if self.bits(wall.1).get(wall.0) {
+            match self.one_way.get(&wall) {
+                None => DoorState::Open,
+                Some(&way) if way == dir => DoorState::OneWayOut,
+                Some(_) => DoorState::OneWayIn,
+            }
+        } else if let Some(&key) = self.locks.get(&wall) {
+            DoorState::Locked(key)
+        } else {
+            DoorState::Closed
+        })
+    }
+    /// The room at `ix`. A crossing only has the doors of the corridor on top,
+    /// as the one underneath doesn't lead in or out of it.
+    pub fn room(&self, ix: Ix) -> Room {
+        let kind = self.kind(ix);
+        let door = |dir| self.door(ix, dir).filter(|_| !kind.is_under(dir));
+        Room {
+            row: ix.y(),
+            col: ix.x(),
+            doors: Doors {
+                north: door(Direction::North),
+                east: door(Direction::East),
+                south: door(Direction::South),
+                west: door(Direction::West),
+            },
+            key: self.keys.get(&ix).copied(),
+            wrapped: [
+                Direction::North,
+                Direction::South,
+                Direction::East,
+                Direction::West,
+            ]
+            .map(|dir| self.door(ix, dir).is_some() && self.wraps(ix, dir)),
+            kind,
+        }
+    }
+    /// The wall on the `dir` side of `ix`, as the room it's stored on and
+    /// whether it's that room's east or south wall. `None` if there's no door there.
+    fn wall(&self, ix: Ix, dir: Direction) -> Option<(Ix, Direction)> {
+        let next = self
+            .neighbor(ix, dir)
+            .filter(|&n| self.is_active(ix) && self.is_active(n))?;
+        Some(match dir {
+            Direction::North => (next, Direction::South),
+            Direction::East => (ix, Direction::East),
+            Direction::South => (ix, Direction::South),
+            Direction::West => (next, Direction::East),
+        })
+    }
+    fn bits(&self, side: Direction) -> &BitGrid {
+        match side {
+            Direction::East | Direction::West => &self.open_east,
+            Direction::North | Direction::South => &self.open_south,
+        }
+    }
+    /// Opens the door on the `dir` side of `ix`, which is also the door on the
+    /// opposite side of its neighbor. Does nothing if there's no door there.
+    pub fn open(&mut self, ix: Ix, dir: Direction) {
+        self.set_door(ix, dir, true);
+    }
+    pub fn close(&mut self, ix: Ix, dir: Direction) {
+        self.set_door(ix, dir, false);
+    }
+    /// Opens the door on the `dir` side of `ix` so it can only be passed going
+    /// out of `ix`.
+    pub fn open_one_way(&mut self, ix: Ix, dir: Direction) {
+        self.open(ix, dir);
+        if let Some(wall) = self.wall(ix, dir) {
+            self.one_way.insert(wall, dir);
+        }
+    }
+    /// Closes the door on the `dir` side of `ix` so that only `key` opens it.
+    pub fn lock(&mut self, ix: Ix, dir: Direction, key: KeyId) {
+        self.close(ix, dir);
+        if let Some(wall) = self.wall(ix, dir) {
+            self.locks.insert(wall, key);
+        }
+    }
+    fn set_door(&mut self, ix: Ix, dir: Direction, open: bool) {
+        let Some(wall @ (at, side)) = self.wall(ix, dir) else {
+            return;
+        };
+        self.locks.remove(&wall);
+        self.one_way.remove(&wall);
+        match side {
+            Direction::North | Direction::South => self.open_south.set(at, open),
+            Direction::East | Direction::West => self.open_east.set(at, open),
+        }
+    }
+    #[deprecated(note = "use `open(ix, Direction::North)`")]
+    pub fn open_north(&mut self, ix: Ix) {
+        self.open(ix, Direction::North);
+    }
+    #[deprecated(note = "use `open(ix, Direction::East)`")]
+    pub fn open_east(&mut self, ix: Ix) {
+        self.open(ix, Direction::East);
+    }
+    #[deprecated(note = "use `open(ix, Direction::South)`")]
+    pub fn open_south(&mut self, ix: Ix) {
+        self.open(ix, Direction::South);
+    }
+    #[deprecated(note = "use `open(ix, Direction::West)`")]
+    pub fn open_west(&mut self, ix: Ix) {
+        self.open(ix, Direction::West);
+    }
+    #[deprecated(note = "use `close(ix, Direction::North)`")]
+    pub fn close_north(&mut self, ix: Ix) {
+        self.close(ix, Direction::North);
+    }
+    #[deprecated(note = "use `close(ix, Direction::East)`")]
+    pub fn close_east(&mut self, ix: Ix) {
+        self.close(ix, Direction::East);
+    }
+    #[deprecated(note = "use `close(ix, Direction::South)`")]
+    pub fn close_south(&mut self, ix: Ix) {
+        self.close(ix, Direction::South);
+    }
+    #[deprecated(note = "use `close(ix, Direction::West)`")]
+    pub fn close_west(&mut self, ix: Ix) {
+        self.close(ix, Direction::West);
+    }
+    #[deprecated(note = "use `try_move(Direction::North)`")]
+    pub fn move_north(&mut self) -> bool {
+        self.try_move(Direction::North).is_ok()
+    }
+    #[deprecated(note = "use `try_move(Direction::East)`")]
+    pub fn move_east(&mut self) -> bool {
+        self.try_move(Direction::East).is_ok()
+    }
+    #[deprecated(note = "use `try_move(Direction::South)`")]
+    pub fn move_south(&mut self) -> bool {
+        self.try_move(Direction::South).is_ok()
+    }
+    #[deprecated(note = "use `try_move(Direction::West)`")]
+    pub fn move_west(&mut self) -> bool {
+        self.try_move(Direction::West).is_ok()
+    }
+    /// Moves the player one room `dir`, or says why they can't go that way.
+    /// Locked doors open for the player if they hold the key, and any key in
+    /// the room they move into is picked up. Going straight on into a crossing
+    /// along the corridor underneath comes out the other side of it.
+    pub fn try_move(&mut self, dir: Direction) -> MoveResult {
+        if self.kind(self.current_ix).is_under(dir) {
+            return Err(MoveError::Underneath);
+        }
+        let next = self.through(self.current_ix, dir).ok_or(MoveError::Edge)?;
+        match self.door(self.current_ix, dir) {
+            None => Err(MoveError::NoRoom),
+            Some(DoorState::Closed) => Err(MoveError::Closed),
+            Some(DoorState::OneWayIn) => Err(MoveError::OneWay),
+            Some(DoorState::Locked(key)) if !self.held.contains(&key) => {
+                Err(MoveError::Locked(key))
+            }
+            Some(st) => {
+                if let DoorState::Locked(_) = st {
+                    self.open(self.current_ix, dir);
+                }
+                self.current_ix = next;
+                if let Some(key) = self.keys.remove(&next) {
+                    self.held.insert(key);
+                }
+                Ok(next)
+            }
+        }
+    }
+    /// The rooms reachable from `ix` through a single open door, counting
+    /// one-way doors only going their way, and passing under any crossing.
+    pub fn open_neighbors(&self, ix: Ix) -> impl Iterator<Item = Ix> + use<'_> {
+        self.room(ix)
+            .all_doors()
+            .filter(|&(_, st)| st.is_passable())
+            .filter_map(move |(dir, _)| self.through(ix, dir))
+    }
+    /// How many doors are open in the whole maze.
+    pub fn open_door_count(&self) -> usize {
+        self.open_east.count() + self.open_south.count()
+    }
+    pub fn is_done(&self) -> bool {
+        self.current_ix == self.goal
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        let m = Maze::new(3, 3);
+        assert_eq!(
+            Doors {
+                north: None,
+                east: Some(DoorState::Closed),
+                south: Some(DoorState::Closed),
+                west: None,
+            },
+            m.room(Size::new(3, 3).ix(0, 0).unwrap()).doors,
+            "0, 0"
+        );
+        assert_eq!(
+            Doors {
+                north: None,
+                east: Some(DoorState::Closed),
+                south: Some(DoorState::Closed),
+                west: Some(DoorState::Closed),
+            },
+            m.room(Size::new(3, 3).ix(0, 1).unwrap()).doors,
+            "0, 1"
+        );
+        assert_eq!(
+            Doors {
+                north: None,
+                east: None,
+                south: Some(DoorState::Closed),
+                west: Some(DoorState::Closed),
+            },
+            m.room(Size::new(3, 3).ix(0, 2).unwrap()).doors,
+            "0, 2"
+        );
+        assert_eq!(
+            Doors {
+                north: Some(DoorState::Closed),
+                east: Some(DoorState::Closed),
+                south: Some(DoorState::Closed),
+                west: None,
+            },
+            m.room(Size::new(3, 3).ix(1, 0).unwrap()).doors,
+            "1, 0"
+        );
+        assert_eq!(
+            Doors {
+                north: Some(DoorState::Closed),
+                east: Some(DoorState::Closed),
+                south: Some(DoorState::Closed),
+                west: Some(DoorState::Closed),
+            },
+            m.room(Size::new(3, 3).ix(1, 1).unwrap()).doors,
+            "1, 1"
+        );
+        assert_eq!(
+            Doors {
+                north: Some(DoorState::Closed),
+                east: None,
+                south: Some(DoorState::Closed),
+                west: Some(DoorState::Closed),
+            },
+            m.room(Size::new(3, 3).ix(1, 2).unwrap()).doors,
+            "1, 2"
+        );
+        assert_eq!(
+            Doors {
+                north: Some(DoorState::Closed),
+                east: Some(DoorState::Closed),
+                south: None,
+                west: None,
+            },
+            m.room(Size::new(3, 3).ix(2, 0).unwrap()).doors,
+            "2,0"
+        );
+        assert_eq!(
+            Doors {
+                north: Some(DoorState::Closed),
+                east: Some(DoorState::Closed),
+                south: None,
+                west: Some(DoorState::Closed),
+            },
+            m.room(Size::new(3, 3).ix(2, 1).unwrap()).doors,
+            "2,1"
+        );
+        assert_eq!(
+            Doors {
+                north: Some(DoorState::Closed),
+                east: None,
+                south: None,
+                west: Some(DoorState::Closed),
+            },
+            m.room(Size::new(3, 3).ix(2, 2).unwrap()).doors,
+            "2,2"
+        );
+    }
+
+    #[test]
+    fn test_open_east() {
+        let mut m = Maze::new(3, 3);
+        let ix = Size::new(3, 3).ix(0, 0).unwrap();
+        dbg!(&m.room(ix).doors);
+        m.open(ix, Direction::East);
+        assert_eq!(
+            Some(DoorState::Open),
+            m.room(ix).doors.east,
+            "original room"
+        );
+        let ix2 = Size::new(3, 3).ix(0, 1).unwrap();
+        assert_eq!(Some(DoorState::Open), m.room(ix2).doors.west, "neighbor");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_old_door_methods() {
+        let mut m = Maze::new(2, 2);
+        let ix = |row, col| Size::new(2, 2).ix(row, col).unwrap();
+        m.open_east(ix(0, 0));
+        m.open_north(ix(1, 1));
+        m.close_west(ix(0, 1));
+        assert_eq!(Some(DoorState::Closed), m.door(ix(0, 0), Direction::East));
+        assert_eq!(Some(DoorState::Open), m.door(ix(0, 1), Direction::South));
+        assert!(!m.move_east());
+        m.current_ix = ix(0, 1);
+        assert!(m.move_south());
+        assert_eq!(ix(1, 1), m.current_ix);
+    }
+    #[test]
+    fn test_open_west() {
+        let mut m = Maze::new(3, 3);
+        let ix = Size::new(3, 3).ix(0, 1).unwrap();
+        dbg!(&m.room(ix).doors);
+        m.open(ix, Direction::West);
+        assert_eq!(
+            Some(DoorState::Open),
+            m.room(ix).doors.west,
+            "original room"
+        );
+        let ix2 = Size::new(3, 3).ix(0, 0).unwrap();
+        assert_eq!(Some(DoorState::Open), m.room(ix2).doors.east, "neighbor");
+    }
+
+    #[test]
+    fn test_walls_stored_once() {
+        let mut m = Maze::new(1000, 1000);
+        let ix = m.size().ix(500, 500).unwrap();
+        m.open(ix, Direction::South);
+        m.open(ix, Direction::West);
+        assert_eq!(
+            Some(DoorState::Open),
+            m.door(ix.south().unwrap(), Direction::North)
+        );
+        assert_eq!(
+            Some(DoorState::Open),
+            m.door(ix.west().unwrap(), Direction::East)
+        );
+        assert_eq!(2, m.open_door_count());
+        m.close(ix.south().unwrap(), Direction::North);
+        assert_eq!(Some(DoorState::Closed), m.door(ix, Direction::South));
+        assert_eq!(1, m.open_door_count());
+        assert_eq!("room (500, 500)", m.room(ix).description());
+    }
+
+    #[test]
+    fn test_with_mask() {
+        let mask = Mask::parse("##.\n.##").unwrap();
+        let mut m = Maze::with_mask(mask);
+        let ix = |row, col| Size::new(2, 3).ix(row, col).unwrap();
+        assert_eq!(ix(0, 0), m.current_ix);
+        assert_eq!(ix(1, 2), m.goal);
+        assert_eq!(
+            Doors {
+                north: None,
+                east: None,
+                south: None,
+                west: None,
+            },
+            m.room(ix(0, 2)).doors,
+            "masked"
+        );
+        assert_eq!(
+            Doors {
+                north: None,
+                east: None,
+                south: Some(DoorState::Closed),
+                west: Some(DoorState::Closed),
+            },
+            m.room(ix(0, 1)).doors,
+            "next to masked"
+        );
+        m.open(ix(0, 1), Direction::East);
+        assert_eq!(None, m.room(ix(0, 1)).doors.east);
+        assert_eq!(None, m.room(ix(0, 2)).doors.west);
+    }
+
+    #[test]
+    fn test_try_move() {
+        let mask = Mask::parse("##.\n.##").unwrap();
+        let mut m = Maze::with_mask(mask);
+        let ix = |row, col| Size::new(2, 3).ix(row, col).unwrap();
+        assert_eq!(Err(MoveError::Edge), m.try_move(Direction::North));
+        assert_eq!(Err(MoveError::NoRoom), m.try_move(Direction::South));
+        assert_eq!(Err(MoveError::Closed), m.try_move(Direction::East));
+        m.open(ix(0, 0), Direction::East);
+        m.open(ix(1, 1), Direction::North);
+        assert_eq!(Ok(ix(0, 1)), m.try_move(Direction::East));
+        assert_eq!(Ok(ix(1, 1)), m.try_move(Direction::South));
+        assert_eq!(ix(1, 1), m.current_ix);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let mut m = Maze::new(3, 4);
+        let ix = |row, col| Size::new(3, 4).ix(row, col).unwrap();
+        m.wrap = Wrap::Torus;
+        assert_eq!(Some(ix(2, 0)), m.neighbor(ix(0, 0), Direction::North));
+        assert_eq!(Some(ix(0, 3)), m.neighbor(ix(0, 0), Direction::West));
+        assert_eq!(Err(MoveError::Closed), m.try_move(Direction::North));
+        m.open(ix(0, 0), Direction::North);
+        assert_eq!(Some(DoorState::Open), m.door(ix(2, 0), Direction::South));
+        assert!(m.room(ix(2, 0)).wraps(Direction::South));
+        assert!(!m.room(ix(2, 0)).wraps(Direction::North));
+        assert_eq!(Ok(ix(2, 0)), m.try_move(Direction::North));
+        assert_eq!(Ok(ix(0, 0)), m.try_move(Direction::South));
+
+        // the half twist brings you back in upside down
+        m.wrap = Wrap::Mobius;
+        assert_eq!(None, m.neighbor(ix(0, 0), Direction::North));
+        assert_eq!(Some(ix(2, 3)), m.neighbor(ix(0, 0), Direction::West));
+        m.open(ix(0, 3), Direction::East);
+        assert_eq!(Some(DoorState::Open), m.door(ix(2, 0), Direction::West));
+        assert_eq!(Some(DoorState::Closed), m.door(ix(0, 0), Direction::West));
+        m.current_ix = ix(0, 3);
+        assert_eq!(Ok(ix(2, 0)), m.try_move(Direction::East));
+    }
+}
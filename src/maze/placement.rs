@@ -0,0 +1,248 @@
+use super::{Maze, analysis::distances};
+use crate::grid::Ix;
+use rand::{Rng, seq::IndexedRandom};
+
+/// Where the player starts and where the goal goes.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Placement {
+    /// Start in the first room of the north row, goal in the last room of the south one.
+    #[default]
+    Corners,
+    /// Two different random rooms.
+    Random,
+    /// The two rooms furthest apart, by steps through open doors.
+    FarthestPair,
+    /// Keep the current start and put the goal as far from it as possible.
+    FarthestFromStart,
+    /// Start in the middle, goal on the furthest room on the edge of the grid or
+    /// next to a masked-out room.
+    CentreToBorder,
+}
+
+impl Placement {
+    pub const ALL: [Placement; 5] = [
+        Placement::Corners,
+        Placement::Random,
+        Placement::FarthestPair,
+        Placement::FarthestFromStart,
+        Placement::CentreToBorder,
+    ];
+
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|p| p == self).unwrap_or(0)
+    }
+    pub fn from_index(ix: usize) -> Option<Self> {
+        Self::ALL.get(ix).copied()
+    }
+    pub fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Placement::Corners => "corners",
+            Placement::Random => "random",
+            Placement::FarthestPair => "farthest pair",
+            Placement::FarthestFromStart => "farthest from start",
+            Placement::CentreToBorder => "centre to border",
+        }
+    }
+}
+
+impl Maze {
+    /// Moves `current_ix` and `goal` according to `placement`. The distance based
+    /// placements follow open doors, so this should run after the doors are seeded.
+    pub fn place(&mut self, placement: Placement, rng: &mut impl Rng) {
+        match placement {
+            Placement::Corners => {
+                let mut active = self.mask.active_rooms();
+                self.current_ix = active.next().unwrap();
+                self.goal = active.last().unwrap_or(self.current_ix);
+            }
+            Placement::Random => {
+                let all: Vec<Ix> = self.mask.active_rooms().collect();
+                let picked: Vec<&Ix> = all.choose_multiple(rng, 2).collect();
+                self.current_ix = *picked[0];
+                self.goal = **picked.last().unwrap();
+            }
+            Placement::FarthestPair => {
+                let (start, _) = self.farthest_from(self.current_ix);
+                let (goal, _) = self.farthest_from(start);
+                self.current_ix = start;
+                self.goal = goal;
+            }
+            Placement::FarthestFromStart => {
+                self.goal = self.farthest_from(self.current_ix).0;
+            }
+            Placement::CentreToBorder => {
+                let (mid_row, mid_col) = (self.n_rows() / 2, self.n_cols() / 2);
+                self.current_ix = self
+                    .mask
+                    .active_rooms()
+                    .min_by_key(|ix| ix.y().abs_diff(mid_row) + ix.x().abs_diff(mid_col))
+                    .unwrap();
+                self.goal = self
+                    .farthest_on_border()
+                    // nothing on the border is reachable, so any of it will do
+                    .unwrap_or_else(|| *self.border().choose(rng).unwrap());
+            }
+        }
+    }
+
+    /// Moves whichever of the start and goal `placement` leaves free, until the
+    /// shortest path between them is at least `min_len` steps, or they're as
+    /// far apart as this maze and `placement` allow. The corners stay put, and
+    /// so does the start when the goal is placed from it; a goal on the border
+    /// stays on the border. With random placement or the farthest pair, the
+    /// goal moves first, then the start if that isn't enough.
+    pub fn stretch(&mut self, placement: Placement, min_len: usize) {
+        let dists = distances(self, self.current_ix);
+        if dists.get(&self.goal).is_some_and(|&d| d >= min_len) {
+            return;
+        }
+        match placement {
+            Placement::Corners => (),
+            Placement::FarthestFromStart => self.goal = self.farthest_from(self.current_ix).0,
+            Placement::CentreToBorder => {
+                if let Some(goal) = self.farthest_on_border() {
+                    self.goal = goal;
+                }
+            }
+            Placement::Random | Placement::FarthestPair => {
+                let (goal, len) = self.farthest_from(self.current_ix);
+                if len >= min_len {
+                    self.goal = goal;
+                } else {
+                    self.current_ix = goal;
+                    self.goal = self.farthest_from(goal).0;
+                }
+            }
+        }
+    }
+    /// The rooms on the edge of the grid or next to a masked-out room, which
+    /// is where a missing door leads. A torus has neither, so that's all of them.
+    fn border(&self) -> Vec<Ix> {
+        let border: Vec<Ix> = self
+            .mask
+            .active_rooms()
+            .filter(|&ix| self.room(ix).available_directions().count() < 4)
+            .collect();
+        if border.is_empty() {
+            self.mask.active_rooms().collect()
+        } else {
+            border
+        }
+    }
+    /// The reachable room on the border furthest from the start.
+    fn farthest_on_border(&self) -> Option<Ix> {
+        let dists = distances(self, self.current_ix);
+        self.border()
+            .into_iter()
+            .filter_map(|ix| dists.get(&ix).map(|&d| (d, ix)))
+            .max()
+            .map(|(_, ix)| ix)
+    }
+    /// The reachable room furthest from `from`, and how many steps away it is.
+    fn farthest_from(&self, from: Ix) -> (Ix, usize) {
+        distances(self, from)
+            .into_iter()
+            .max_by_key(|&(_, d)| d)
+            .unwrap_or((from, 0))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::maze::mask::Mask;
+    use crate::{Direction, grid::Size};
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn corridor() -> Maze {
+        // the corridor bends back: (0, 2) is only reachable from (0, 3)
+        let mut m = Maze::new(1, 5);
+        for col in 0..4 {
+            m.open(m.size().ix(0, col).unwrap(), Direction::East);
+        }
+        m
+    }
+
+    #[test]
+    fn test_farthest_pair() {
+        let mut m = corridor();
+        m.current_ix = m.size().ix(0, 2).unwrap();
+        m.place(Placement::FarthestPair, &mut StdRng::seed_from_u64(1));
+        let ends = [m.current_ix.x(), m.goal.x()];
+        assert!(ends == [0, 4] || ends == [4, 0], "{ends:?}");
+    }
+
+    #[test]
+    fn test_farthest_from_start() {
+        let mut m = corridor();
+        m.current_ix = m.size().ix(0, 1).unwrap();
+        m.place(Placement::FarthestFromStart, &mut StdRng::seed_from_u64(1));
+        assert_eq!(m.size().ix(0, 1).unwrap(), m.current_ix);
+        assert_eq!(m.size().ix(0, 4).unwrap(), m.goal);
+    }
+
+    #[test]
+    fn test_stretch() {
+        let mut m = corridor();
+        let ix = |col| Size::new(1, 5).ix(0, col).unwrap();
+        (m.current_ix, m.goal) = (ix(1), ix(2));
+        // already far enough apart
+        m.stretch(Placement::Random, 1);
+        assert_eq!((ix(1), ix(2)), (m.current_ix, m.goal));
+        // the corners don't move
+        m.stretch(Placement::Corners, 3);
+        assert_eq!((ix(1), ix(2)), (m.current_ix, m.goal));
+        // the goal moves as far as it can from the start
+        m.stretch(Placement::Random, 3);
+        assert_eq!((ix(1), ix(4)), (m.current_ix, m.goal));
+        // but not the start, if the goal was placed from it
+        m.stretch(Placement::FarthestFromStart, 4);
+        assert_eq!((ix(1), ix(4)), (m.current_ix, m.goal));
+        // otherwise the start has to move too
+        m.stretch(Placement::FarthestPair, 4);
+        assert_eq!((ix(4), ix(0)), (m.current_ix, m.goal));
+        // and that's as far as it goes
+        m.stretch(Placement::FarthestPair, 10);
+        assert_eq!(4, distances(&m, m.current_ix)[&m.goal]);
+    }
+
+    #[test]
+    fn test_random_distinct() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut m = Maze::new(2, 2);
+        for _ in 0..50 {
+            m.place(Placement::Random, &mut rng);
+            assert_ne!(m.current_ix, m.goal);
+        }
+    }
+
+    #[test]
+    fn test_centre_to_border() {
+        let mut m = Maze::new(5, 5);
+        for ix in Size::new(5, 5).indices() {
+            m.open(ix, Direction::East);
+            m.open(ix, Direction::South);
+        }
+        m.place(Placement::CentreToBorder, &mut StdRng::seed_from_u64(3));
+        assert_eq!(m.size().ix(2, 2).unwrap(), m.current_ix);
+        // every corner is four steps away, more than any other border room
+        assert!([0, 4].contains(&m.goal.x()) && [0, 4].contains(&m.goal.y()));
+    }
+
+    #[test]
+    fn test_placement_masked() {
+        let mask = Mask::parse(".#.\n###\n.#.").unwrap();
+        let mut m = Maze::with_mask(mask);
+        let mut rng = StdRng::seed_from_u64(4);
+        m.place(Placement::Corners, &mut rng);
+        assert_eq!(m.size().ix(0, 1).unwrap(), m.current_ix);
+        assert_eq!(m.size().ix(2, 1).unwrap(), m.goal);
+        for _ in 0..50 {
+            m.place(Placement::Random, &mut rng);
+            assert!(m.is_active(m.current_ix) && m.is_active(m.goal));
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use super::{DoorState, MoveError, MoveResult, topology::Topology};
+use crate::grid::{BitGrid, Ix, Size};
+use std::f64::consts::PI;
+
+/// The ways out of a room in a ring of a polar maze.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum PolarDirection {
+    /// Towards the middle.
+    Inward,
+    /// Towards the rim, through the `k`th of the rooms the next ring splits
+    /// this one into, counting clockwise.
+    Outward(usize),
+    Clockwise,
+    Anticlockwise,
+}
+
+/// How many rooms each of `rings` rings has, from the single room in the middle
+/// out. A ring doubles its rooms whenever they'd otherwise get much wider
+/// than they are deep.
+pub fn ring_counts(rings: usize) -> Vec<usize> {
+    let mut counts = vec![1];
+    for ring in 1..rings {
+        let prev = counts[ring - 1];
+        let circumference = 2.0 * PI * ring as f64;
+        let ratio = (circumference / prev as f64).round().max(1.0) as usize;
+        counts.push(prev * ratio);
+    }
+    counts
+}
+
+/// A maze of concentric rings, with the goal in the middle. Rooms are indexed by
+/// ring (the row) and position clockwise from north in that ring (the column),
+/// so only the first few columns of the inner rows are rooms. Like `Maze`, each
+/// wall is stored once: as the inward wall of the room outside it, or the
+/// clockwise wall of the room anticlockwise of it.
+#[derive(Debug, Clone)]
+pub struct PolarMaze {
+    counts: Vec<usize>,
+    /// Set for every room whose inward door is open.
+    open_inward: BitGrid,
+    /// Set for every room whose clockwise door is open.
+    open_clockwise: BitGrid,
+    pub current_ix: Ix,
+    pub goal: Ix,
+}
+
+impl PolarMaze {
+    /// A maze of `rings` rings, with the player on the rim.
+    pub fn new(rings: usize) -> Self {
+        let counts = ring_counts(rings.max(2));
+        let size = Size::new(counts.len(), *counts.last().unwrap());
+        Self {
+            open_inward: BitGrid::new(size, false),
+            open_clockwise: BitGrid::new(size, false),
+            current_ix: size.ix(counts.len() - 1, 0).unwrap(),
+            goal: size.ix(0, 0).unwrap(),
+            counts,
+        }
+    }
+    pub fn size(&self) -> Size {
+        self.open_inward.size()
+    }
+    pub fn rings(&self) -> usize {
+        self.counts.len()
+    }
+    /// How many rooms ring `ring` has.
+    pub fn ring_len(&self, ring: usize) -> usize {
+        self.counts[ring]
+    }
+    /// How many rooms the next ring out splits each room of `ring` into, or 0
+    /// for the rim.
+    pub fn outward_count(&self, ring: usize) -> usize {
+        self.counts
+            .get(ring + 1)
+            .map_or(0, |next| next / self.counts[ring])
+    }
+    pub fn rooms(&self) -> impl Iterator<Item = Ix> + use<'_> {
+        self.size()
+            .indices()
+            .filter(|ix| ix.x() < self.counts[ix.y()])
+    }
+    /// Every side of `ix` there could be a door on. The rim gets an outward
+    /// side too, so the outside wall is drawn.
+    pub fn directions(&self, ix: Ix) -> Vec<PolarDirection> {
+        let ring = ix.y();
+        let mut dirs = Vec::new();
+        if ring > 0 {
+            dirs.push(PolarDirection::Inward);
+        }
+        if self.counts[ring] > 1 {
+            dirs.extend([PolarDirection::Clockwise, PolarDirection::Anticlockwise]);
+        }
+        dirs.extend((0..self.outward_count(ring).max(1)).map(PolarDirection::Outward));
+        dirs
+    }
+    /// The room next to `ix` in direction `dir`, if there is one.
+    pub fn neighbor(&self, ix: Ix, dir: PolarDirection) -> Option<Ix> {
+        let (ring, cell) = (ix.y(), ix.x());
+        let len = self.counts[ring];
+        let (ring, cell) = match dir {
+            PolarDirection::Inward if ring > 0 => (ring - 1, cell / self.outward_count(ring - 1)),
+            PolarDirection::Outward(k) if k < self.outward_count(ring) => {
+                (ring + 1, cell * self.outward_count(ring) + k)
+            }
+            PolarDirection::Clockwise if len > 1 => (ring, (cell + 1) % len),
+            PolarDirection::Anticlockwise if len > 1 => (ring, (cell + len - 1) % len),
+            _ => return None,
+        };
+        self.size().ix(ring, cell)
+    }
+    /// The wall on the `dir` side of `ix`, as the room it's stored on and which
+    /// of that room's walls it is. `None` if there's no door there.
+    fn wall(&self, ix: Ix, dir: PolarDirection) -> Option<(Ix, PolarDirection)> {
+        let next = self.neighbor(ix, dir)?;
+        Some(match dir {
+            PolarDirection::Inward | PolarDirection::Clockwise => (ix, dir),
+            PolarDirection::Outward(_) => (next, PolarDirection::Inward),
+            PolarDirection::Anticlockwise => (next, PolarDirection::Clockwise),
+        })
+    }
+    fn bits(&mut self, side: PolarDirection) -> &mut BitGrid {
+        match side {
+            PolarDirection::Inward => &mut self.open_inward,
+            _ => &mut self.open_clockwise,
+        }
+    }
+    /// The door on the `dir` side of `ix`, if there is one.
+    pub fn door(&self, ix: Ix, dir: PolarDirection) -> Option<DoorState> {
+        let (at, side) = self.wall(ix, dir)?;
+        let bits = match side {
+            PolarDirection::Inward => &self.open_inward,
+            _ => &self.open_clockwise,
+        };
+        Some(if bits.get(at) {
+            DoorState::Open
+        } else {
+            DoorState::Closed
+        })
+    }
+    pub fn doors(&self, ix: Ix) -> Vec<(PolarDirection, Option<DoorState>)> {
+        self.directions(ix)
+            .into_iter()
+            .map(|dir| (dir, self.door(ix, dir)))
+            .collect()
+    }
+    pub fn open(&mut self, ix: Ix, dir: PolarDirection) {
+        if let Some((at, side)) = self.wall(ix, dir) {
+            self.bits(side).set(at, true);
+        }
+    }
+    pub fn close(&mut self, ix: Ix, dir: PolarDirection) {
+        if let Some((at, side)) = self.wall(ix, dir) {
+            self.bits(side).set(at, false);
+        }
+    }
+    /// Moves the player one room `dir`, or says why they can't go that way.
+    pub fn try_move(&mut self, dir: PolarDirection) -> MoveResult {
+        let next = self.neighbor(self.current_ix, dir).ok_or(MoveError::Edge)?;
+        match self.door(self.current_ix, dir) {
+            Some(DoorState::Open) => {
+                self.current_ix = next;
+                Ok(next)
+            }
+            _ => Err(MoveError::Closed),
+        }
+    }
+    /// The first open outward door of `ix`, or the first outward door if none
+    /// of them are open.
+    pub fn way_out(&self, ix: Ix) -> PolarDirection {
+        (0..self.outward_count(ix.y()))
+            .map(PolarDirection::Outward)
+            .find(|&dir| self.door(ix, dir) == Some(DoorState::Open))
+            .unwrap_or(PolarDirection::Outward(0))
+    }
+    /// The rooms reachable from `ix` through a single open door.
+    pub fn open_neighbors(&self, ix: Ix) -> impl Iterator<Item = Ix> + use<'_> {
+        self.directions(ix)
+            .into_iter()
+            .filter(move |&dir| self.door(ix, dir) == Some(DoorState::Open))
+            .filter_map(move |dir| self.neighbor(ix, dir))
+    }
+    pub fn open_door_count(&self) -> usize {
+        self.open_inward.count() + self.open_clockwise.count()
+    }
+    pub fn is_done(&self) -> bool {
+        self.current_ix == self.goal
+    }
+}
+
+impl Topology for PolarMaze {
+    type Dir = PolarDirection;
+
+    fn start(&self) -> Ix {
+        self.current_ix
+    }
+    fn rooms(&self) -> impl Iterator<Item = Ix> {
+        PolarMaze::rooms(self)
+    }
+    fn exits(&self, ix: Ix) -> Vec<(PolarDirection, Ix)> {
+        self.directions(ix)
+            .into_iter()
+            .filter_map(|dir| self.neighbor(ix, dir).map(|next| (dir, next)))
+            .collect()
+    }
+    fn open(&mut self, ix: Ix, dir: PolarDirection) {
+        PolarMaze::open(self, ix, dir);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ring_counts() {
+        assert_eq!(vec![1, 6, 12, 24, 24, 24, 48], ring_counts(7));
+    }
+
+    #[test]
+    fn test_polar_neighbors() {
+        let m = PolarMaze::new(4);
+        let size = m.size();
+        let ix = |ring, cell| size.ix(ring, cell).unwrap();
+        assert_eq!(1 + 6 + 12 + 24, m.rooms().count());
+        // the middle room only has ways out
+        assert_eq!(6, m.directions(ix(0, 0)).len());
+        assert_eq!(
+            Some(ix(1, 5)),
+            m.neighbor(ix(0, 0), PolarDirection::Outward(5))
+        );
+        assert_eq!(None, m.neighbor(ix(0, 0), PolarDirection::Clockwise));
+        // rings wrap around
+        assert_eq!(
+            Some(ix(2, 0)),
+            m.neighbor(ix(2, 11), PolarDirection::Clockwise)
+        );
+        assert_eq!(
+            Some(ix(2, 11)),
+            m.neighbor(ix(2, 0), PolarDirection::Anticlockwise)
+        );
+        // ring 1 splits in two, the rim doesn't lead anywhere
+        assert_eq!(
+            Some(ix(2, 7)),
+            m.neighbor(ix(1, 3), PolarDirection::Outward(1))
+        );
+        assert_eq!(None, m.neighbor(ix(1, 3), PolarDirection::Outward(2)));
+        assert_eq!(None, m.neighbor(ix(3, 3), PolarDirection::Outward(0)));
+        // every way out has a way back in
+        for ix in m.rooms() {
+            for dir in m.directions(ix) {
+                if let Some(next) = m.neighbor(ix, dir) {
+                    let back = m
+                        .directions(next)
+                        .into_iter()
+                        .filter(|&d| m.neighbor(next, d) == Some(ix))
+                        .count();
+                    assert!(back >= 1, "{ix:?} {dir:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_polar_doors() {
+        let mut m = PolarMaze::new(3);
+        let size = m.size();
+        let ix = |ring, cell| size.ix(ring, cell).unwrap();
+        m.open(ix(2, 0), PolarDirection::Inward);
+        assert_eq!(
+            Some(DoorState::Open),
+            m.door(ix(1, 0), PolarDirection::Outward(0))
+        );
+        assert_eq!(
+            Some(DoorState::Closed),
+            m.door(ix(1, 0), PolarDirection::Outward(1))
+        );
+        m.open(ix(1, 1), PolarDirection::Anticlockwise);
+        assert_eq!(
+            Some(DoorState::Open),
+            m.door(ix(1, 0), PolarDirection::Clockwise)
+        );
+        m.open(ix(0, 0), PolarDirection::Outward(1));
+        assert_eq!(3, m.open_door_count());
+        assert_eq!(PolarDirection::Outward(1), m.way_out(ix(0, 0)));
+        assert_eq!(Err(MoveError::Edge), m.try_move(PolarDirection::Outward(0)));
+        assert_eq!(
+            Err(MoveError::Closed),
+            m.try_move(PolarDirection::Clockwise)
+        );
+        assert_eq!(Ok(ix(1, 0)), m.try_move(PolarDirection::Inward));
+        assert_eq!(Ok(ix(1, 1)), m.try_move(PolarDirection::Clockwise));
+        assert_eq!(Ok(ix(0, 0)), m.try_move(PolarDirection::Inward));
+        assert!(m.is_done());
+    }
+}
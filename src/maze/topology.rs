@@ -0,0 +1,39 @@
+use super::Maze;
+use crate::{Direction, grid::Ix};
+use std::fmt;
+
+/// The shape of a maze: which rooms it has and which rooms they're next to.
+/// Generators written against this work whatever shape the rooms are.
+pub trait Topology {
+    /// The ways out of a room.
+    type Dir: Copy + Eq + fmt::Debug;
+
+    /// Where the player starts, which generators grow the maze out from.
+    fn start(&self) -> Ix;
+    /// Every room, in a fixed order.
+    fn rooms(&self) -> impl Iterator<Item = Ix>;
+    /// The doors out of `ix`, open or not, and the rooms they lead to.
+    fn exits(&self, ix: Ix) -> Vec<(Self::Dir, Ix)>;
+    /// Opens the door on the `dir` side of `ix`, from both sides.
+    fn open(&mut self, ix: Ix, dir: Self::Dir);
+}
+
+impl Topology for Maze {
+    type Dir = Direction;
+
+    fn start(&self) -> Ix {
+        self.current_ix
+    }
+    fn rooms(&self) -> impl Iterator<Item = Ix> {
+        self.mask.active_rooms()
+    }
+    fn exits(&self, ix: Ix) -> Vec<(Direction, Ix)> {
+        self.room(ix)
+            .available_directions()
+            .filter_map(|dir| self.neighbor(ix, dir).map(|next| (dir, next)))
+            .collect()
+    }
+    fn open(&mut self, ix: Ix, dir: Direction) {
+        Maze::open(self, ix, dir);
+    }
+}
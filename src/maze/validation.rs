@@ -0,0 +1,182 @@
+use super::{DoorState, Maze};
+use crate::{Direction, grid::Ix};
+
+/// Something wrong with a maze's doors, as found by `Maze::validate`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Problem {
+    /// No route through open doors leads from `current_ix` to this room, even
+    /// going the wrong way through one-way doors.
+    Unreachable(Ix),
+    /// The corridor under this crossing is open on this side of it but not on
+    /// the other, so it leads nowhere. A plain door is only stored once, so its
+    /// two sides can't disagree.
+    AsymmetricDoor(Ix, Direction),
+    /// The door is open but leads off the edge of the grid, or into a
+    /// masked-out room, which happens if the mask or wrap changes after seeding.
+    DoorOffGrid(Ix, Direction),
+}
+
+impl Maze {
+    /// Checks that no open door leads off the grid, that the corridors under
+    /// crossings go all the way through, and that every active room can be
+    /// reached from the start, unlocking doors with whatever keys are found
+    /// along the way, so a key locked behind its own door shows up as
+    /// unreachable rooms. Rooms that one-way doors only lead out of are red
+    /// herrings rather than problems, so they count as reached, but not any
+    /// keys in them.
+    pub fn validate(&self) -> Result<(), Vec<Problem>> {
+        let mut problems = Vec::new();
+        for ix in self.size().indices() {
+            for side in [Direction::East, Direction::South] {
+                if self.bits(side).get(ix) && self.wall(ix, side) != Some((ix, side)) {
+                    problems.push(Problem::DoorOffGrid(ix, side));
+                }
+            }
+        }
+        for (&ix, &kind) in &self.crossings {
+            let dir = if kind.is_under(Direction::East) {
+                Direction::East
+            } else {
+                Direction::South
+            };
+            // a locked door is still a way through, once the key's found
+            let is_open = |dir| {
+                self.door(ix, dir)
+                    .is_some_and(|st| st.is_open() || matches!(st, DoorState::Locked(_)))
+            };
+            match (is_open(dir.opposite()), is_open(dir)) {
+                (true, false) => problems.push(Problem::AsymmetricDoor(ix, dir.opposite())),
+                (false, true) => problems.push(Problem::AsymmetricDoor(ix, dir)),
+                _ => {}
+            }
+        }
+        let (_, keys) = self.reachable_collecting_keys();
+        let reachable = self.reachable_through(self.current_ix, &keys, DoorState::is_open);
+        problems.extend(
+            self.mask
+                .active_rooms()
+                .filter(|ix| !reachable.contains(ix))
+                .map(Problem::Unreachable),
+        );
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        game::{Generator, Seeder, braid, one_way},
+        grid::Size,
+        maze::{keys::lock_doors, mask::Mask, placement::Placement, wrap::Wrap},
+    };
+    use rand::{SeedableRng, rngs::StdRng};
+
+    #[test]
+    fn test_validate() {
+        let mut m = Maze::new(2, 2);
+        let ix = |row, col| Size::new(2, 2).ix(row, col).unwrap();
+        m.open(ix(0, 0), Direction::East);
+        m.open(ix(0, 0), Direction::South);
+        m.open(ix(1, 0), Direction::East);
+        assert_eq!(Ok(()), m.validate());
+
+        m.close(ix(1, 1), Direction::West);
+        assert_eq!(Err(vec![Problem::Unreachable(ix(1, 1))]), m.validate());
+    }
+
+    #[test]
+    fn test_validate_off_grid() {
+        let mut m = Maze::new(1, 3);
+        let ix = |col| Size::new(1, 3).ix(0, col).unwrap();
+        m.wrap = Wrap::Cylinder;
+        m.open(ix(0), Direction::East);
+        m.open(ix(1), Direction::East);
+        m.open(ix(2), Direction::East);
+        assert_eq!(Ok(()), m.validate());
+
+        m.wrap = Wrap::None;
+        m.mask = Mask::parse("##.").unwrap();
+        assert_eq!(
+            Err(vec![
+                Problem::DoorOffGrid(ix(1), Direction::East),
+                Problem::DoorOffGrid(ix(2), Direction::East),
+            ]),
+            m.validate()
+        );
+    }
+
+    #[test]
+    fn test_validate_one_way() {
+        for seed in 0..100 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut m = Maze::new(7, 7);
+            Generator::Backtrack.seed(&mut m, &mut rng);
+            braid(&mut m, &mut rng, 50);
+            m.place(Placement::FarthestPair, &mut rng);
+            one_way(&mut m, &mut rng, 30);
+            assert_eq!(Ok(()), m.validate(), "seed {seed}");
+        }
+        // a room that a one-way door only leads out of is a red herring, but
+        // one with no door at all is still unreachable
+        let mut m = Maze::new(1, 3);
+        let ix = |col| Size::new(1, 3).ix(0, col).unwrap();
+        m.open(ix(0), Direction::East);
+        m.open_one_way(ix(2), Direction::West);
+        assert_eq!(Ok(()), m.validate());
+        m.close(ix(1), Direction::East);
+        assert_eq!(Err(vec![Problem::Unreachable(ix(2))]), m.validate());
+    }
+
+    #[test]
+    fn test_validate_locked_weave() {
+        for seed in 0..100 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut m = Maze::new(10, 10);
+            Generator::Weave.seed(&mut m, &mut rng);
+            m.place(Placement::FarthestPair, &mut rng);
+            lock_doors(&mut m, &mut rng, 3);
+            assert_eq!(Ok(()), m.validate(), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn test_validate_crossing() {
+        let mut m = Maze::new(3, 3);
+        let ix = |row, col| Size::new(3, 3).ix(row, col).unwrap();
+        m.open(ix(0, 1), Direction::South);
+        m.open(ix(1, 1), Direction::South);
+        m.tunnel(ix(1, 0), Direction::East);
+        m.close(ix(1, 1), Direction::East);
+        assert_eq!(
+            Some(&Problem::AsymmetricDoor(ix(1, 1), Direction::West)),
+            m.validate().unwrap_err().first()
+        );
+    }
+
+    #[test]
+    fn test_validate_masked() {
+        let mask = Mask::parse("##.").unwrap();
+        let mut m = Maze::with_mask(mask);
+        let ix = |col| Size::new(1, 3).ix(0, col).unwrap();
+        m.open(ix(0), Direction::East);
+        // the masked-out room isn't unreachable, it just isn't there
+        assert_eq!(Ok(()), m.validate());
+        m.open(ix(1), Direction::East);
+        assert_eq!(None, m.door(ix(1), Direction::East));
+    }
+
+    #[test]
+    fn test_validate_unreachable() {
+        let mut m = Maze::new(1, 3);
+        m.open(m.size().ix(0, 0).unwrap(), Direction::East);
+        assert_eq!(
+            Err(vec![Problem::Unreachable(m.size().ix(0, 2).unwrap())]),
+            m.validate()
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use super::{DoorState, Maze};
+use crate::{Direction, grid::Ix};
+
+/// What sort of room a room is, besides its doors.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum RoomKind {
+    #[default]
+    Plain,
+    /// A crossing where the corridor on top runs north to south, over one
+    /// running east to west.
+    OverNorthSouth,
+    /// A crossing where the corridor on top runs east to west, over one
+    /// running north to south.
+    OverEastWest,
+}
+
+impl RoomKind {
+    /// Whether going `dir` through this room goes under the corridor on top.
+    pub fn is_under(self, dir: Direction) -> bool {
+        match self {
+            RoomKind::Plain => false,
+            RoomKind::OverNorthSouth => matches!(dir, Direction::East | Direction::West),
+            RoomKind::OverEastWest => matches!(dir, Direction::North | Direction::South),
+        }
+    }
+}
+
+impl Maze {
+    pub fn kind(&self, ix: Ix) -> RoomKind {
+        self.crossings.get(&ix).copied().unwrap_or_default()
+    }
+    /// The room going `dir` out of `ix` comes out in: the next room, or the one
+    /// past it if the way goes under a crossing there.
+    pub fn through(&self, ix: Ix, dir: Direction) -> Option<Ix> {
+        let next = self.neighbor(ix, dir)?;
+        if self.kind(next).is_under(dir) {
+            self.neighbor(next, dir)
+        } else {
+            Some(next)
+        }
+    }
+    /// Whether a corridor going `dir` out of `ix` could pass under the next
+    /// room, which it can if that room is a plain straight corridor running
+    /// across the way and there's an active room past it.
+    pub fn can_tunnel(&self, ix: Ix, dir: Direction) -> bool {
+        let Some(next) = self.neighbor(ix, dir) else {
+            return false;
+        };
+        let is_open = |ix, dir| self.door(ix, dir) == Some(DoorState::Open);
+        self.door(ix, dir) == Some(DoorState::Closed)
+            && self.kind(next) == RoomKind::Plain
+            && is_open(next, dir.turn_left())
+            && is_open(next, dir.turn_right())
+            && self.room(next).open_count() == 2
+            && self.door(next, dir) == Some(DoorState::Closed)
+    }
+    /// Digs a corridor from `ix` under the next room `dir` to the one past it,
+    /// which `can_tunnel` should have checked is possible. Returns the room
+    /// the corridor comes out in.
+    pub fn tunnel(&mut self, ix: Ix, dir: Direction) -> Option<Ix> {
+        let next = self.neighbor(ix, dir)?;
+        let kind = match dir {
+            Direction::East | Direction::West => RoomKind::OverNorthSouth,
+            Direction::North | Direction::South => RoomKind::OverEastWest,
+        };
+        self.open(ix, dir);
+        self.open(next, dir);
+        self.crossings.insert(next, kind);
+        self.neighbor(next, dir)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{grid::Size, maze::MoveError};
+
+    #[test]
+    fn test_tunnel() {
+        let mut m = Maze::new(3, 3);
+        let ix = |row, col| Size::new(3, 3).ix(row, col).unwrap();
+        // a corridor down the middle column
+        m.open(ix(0, 1), Direction::South);
+        m.open(ix(1, 1), Direction::South);
+        assert!(!m.can_tunnel(ix(1, 1), Direction::East));
+        assert!(!m.can_tunnel(ix(0, 0), Direction::East));
+        assert!(m.can_tunnel(ix(1, 0), Direction::East));
+        assert_eq!(Some(ix(1, 2)), m.tunnel(ix(1, 0), Direction::East));
+        assert_eq!(RoomKind::OverNorthSouth, m.kind(ix(1, 1)));
+        assert!(!m.can_tunnel(ix(1, 0), Direction::East));
+
+        // the crossing only shows the doors of the corridor on top
+        let room = m.room(ix(1, 1));
+        assert_eq!(RoomKind::OverNorthSouth, room.kind);
+        assert_eq!(None, room.doors.east);
+        assert_eq!(Some(DoorState::Open), room.doors.north);
+        assert_eq!(2, room.open_count());
+        assert_eq!(Some(ix(1, 2)), m.through(ix(1, 0), Direction::East));
+        assert_eq!(Some(ix(1, 1)), m.through(ix(0, 1), Direction::South));
+        assert_eq!(
+            vec![ix(1, 2)],
+            m.open_neighbors(ix(1, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![ix(0, 1), ix(2, 1)],
+            m.open_neighbors(ix(1, 1)).collect::<Vec<_>>()
+        );
+
+        // going straight on passes under, and there's no way down from on top
+        m.current_ix = ix(1, 2);
+        assert_eq!(Ok(ix(1, 0)), m.try_move(Direction::West));
+        assert_eq!(Ok(ix(1, 2)), m.try_move(Direction::East));
+        m.current_ix = ix(0, 1);
+        assert_eq!(Ok(ix(1, 1)), m.try_move(Direction::South));
+        assert_eq!(Err(MoveError::Underneath), m.try_move(Direction::East));
+        assert_eq!(Ok(ix(2, 1)), m.try_move(Direction::South));
+    }
+}
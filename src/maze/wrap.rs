@@ -0,0 +1,139 @@
+use crate::grid::{Ix, Size};
+
+/// Which edges of the grid lead round to the opposite edge.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum Wrap {
+    /// Every edge is a wall.
+    #[default]
+    None,
+    /// East and west edges meet.
+    Cylinder,
+    /// East and west edges meet, and so do north and south.
+    Torus,
+    /// East and west edges meet with a half twist, so leaving the east edge
+    /// near the north brings you in on the west edge near the south.
+    Mobius,
+}
+
+impl Wrap {
+    pub const ALL: [Wrap; 4] = [Wrap::None, Wrap::Cylinder, Wrap::Torus, Wrap::Mobius];
+
+    pub fn index(&self) -> usize {
+        Self::ALL.iter().position(|w| w == self).unwrap_or(0)
+    }
+    pub fn from_index(ix: usize) -> Option<Self> {
+        Self::ALL.get(ix).copied()
+    }
+    pub fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % Self::ALL.len()]
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Wrap::None => "none",
+            Wrap::Cylinder => "cylinder",
+            Wrap::Torus => "torus",
+            Wrap::Mobius => "möbius",
+        }
+    }
+    /// Whether the east and west edges of a grid of `size` meet. A grid less
+    /// than three rooms across would have rooms meeting themselves, or the
+    /// same neighbor both ways, so it doesn't wrap.
+    pub fn wraps_cols(&self, size: Size) -> bool {
+        *self != Wrap::None && size.n_cols > 2
+    }
+    pub fn wraps_rows(&self, size: Size) -> bool {
+        *self == Wrap::Torus && size.n_rows > 2
+    }
+    /// The room at `row`, `col` in a grid of `size`, where the row and column
+    /// may be just past its edges.
+    pub fn resolve(&self, size: Size, row: isize, col: isize) -> Option<Ix> {
+        let (n_rows, n_cols) = (size.n_rows as isize, size.n_cols as isize);
+        let (mut row, mut col) = (row, col);
+        if !(0..n_cols).contains(&col) && self.wraps_cols(size) {
+            col = col.rem_euclid(n_cols);
+            if *self == Wrap::Mobius {
+                row = n_rows - 1 - row;
+            }
+        }
+        if !(0..n_rows).contains(&row) && self.wraps_rows(size) {
+            row = row.rem_euclid(n_rows);
+        }
+        size.ix(row.try_into().ok()?, col.try_into().ok()?)
+    }
+    /// Everywhere within one room of the grid that `ix` is, itself first: as
+    /// (row, column, whether it's upside down there).
+    pub fn copies(&self, ix: Ix) -> Vec<(isize, isize, bool)> {
+        let size = ix.size();
+        let (n_rows, n_cols) = (size.n_rows as isize, size.n_cols as isize);
+        let (row, col) = (ix.y() as isize, ix.x() as isize);
+        let near = |at: isize, n: isize| (-1..=n).contains(&at);
+        let mut copies = vec![(row, col, false)];
+        let cols: &[isize] = if self.wraps_cols(size) {
+            &[0, -n_cols, n_cols]
+        } else {
+            &[0]
+        };
+        let rows: &[isize] = if self.wraps_rows(size) {
+            &[0, -n_rows, n_rows]
+        } else {
+            &[0]
+        };
+        for &dc in cols {
+            let flipped = dc != 0 && *self == Wrap::Mobius;
+            let row = if flipped { n_rows - 1 - row } else { row };
+            for &dr in rows {
+                let (r, c) = (row + dr, col + dc);
+                if (dr, dc) != (0, 0) && near(r, n_rows) && near(c, n_cols) {
+                    copies.push((r, c, flipped));
+                }
+            }
+        }
+        copies
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let size = Size::new(3, 4);
+        let ix = |row, col| size.ix(row, col);
+        assert_eq!(None, Wrap::None.resolve(size, 0, 4));
+        assert_eq!(ix(0, 0), Wrap::Cylinder.resolve(size, 0, 4));
+        assert_eq!(None, Wrap::Cylinder.resolve(size, -1, 0));
+        assert_eq!(ix(2, 3), Wrap::Torus.resolve(size, -1, -1));
+        assert_eq!(ix(2, 0), Wrap::Mobius.resolve(size, 0, 4));
+        assert_eq!(ix(1, 3), Wrap::Mobius.resolve(size, 1, -1));
+        // too narrow to wrap
+        assert_eq!(None, Wrap::Torus.resolve(Size::new(2, 2), 0, 2));
+    }
+
+    #[test]
+    fn test_copies() {
+        let size = Size::new(3, 4);
+        let ix = |row, col| size.ix(row, col).unwrap();
+        assert_eq!(vec![(1, 1, false)], Wrap::Torus.copies(ix(1, 1)));
+        assert_eq!(
+            vec![(0, 3, false), (0, -1, false)],
+            Wrap::Cylinder.copies(ix(0, 3))
+        );
+        assert_eq!(
+            vec![(0, 0, false), (3, 0, false), (0, 4, false), (3, 4, false)],
+            Wrap::Torus.copies(ix(0, 0))
+        );
+        assert_eq!(
+            vec![(0, 0, false), (2, 4, true)],
+            Wrap::Mobius.copies(ix(0, 0))
+        );
+        // every copy resolves back to the room
+        for wrap in Wrap::ALL {
+            for ix in size.indices() {
+                for (row, col, _) in wrap.copies(ix) {
+                    assert_eq!(Some(ix), wrap.resolve(size, row, col), "{wrap:?} {ix:?}");
+                }
+            }
+        }
+    }
+}
@@ -1,22 +1,13 @@
-use crate::maze::Maze;
+use crate::{
+    Direction,
+    maze::{Maze, hex::HexDirection},
+};
 use crossterm::event::{Event, KeyCode, KeyEvent};
-use rand::{distr::StandardUniform, prelude::*};
+use rand::{Rng, seq::IndexedRandom};
 
-pub fn random_step<const N_ROWS: usize, const N_COLS: usize>(
-    maze: &mut Maze<N_ROWS, N_COLS>,
-    rng: &mut ThreadRng,
-) {
-    let mut moved = false;
-    while !moved {
-        let v: u8 = rng.sample::<u8, StandardUniform>(StandardUniform) % 4;
-        moved = match v {
-            0 => maze.move_north(),
-            1 => maze.move_south(),
-            2 => maze.move_east(),
-            3 => maze.move_west(),
-            _ => panic!("unreachable"),
-        }
-    }
+pub fn random_step(maze: &mut Maze, rng: &mut impl Rng) {
+    let dirs: Vec<Direction> = Direction::North.into_iter().collect();
+    while maze.try_move(*dirs.choose(rng).unwrap()).is_err() {}
 }
 
 #[derive(Debug, PartialEq)]
@@ -25,12 +16,44 @@ pub enum MazeEvent {
     MoveS,
     MoveE,
     MoveW,
+    MoveNE,
+    MoveSE,
+    MoveSW,
+    MoveNW,
+    /// Up the stairs, on a maze with floors.
+    Up,
+    Down,
     Enter,
     Quit,
     OtherKey(KeyCode),
     Other(Event),
 }
 
+impl MazeEvent {
+    /// Which way a move event goes.
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            MazeEvent::MoveN => Some(Direction::North),
+            MazeEvent::MoveS => Some(Direction::South),
+            MazeEvent::MoveE => Some(Direction::East),
+            MazeEvent::MoveW => Some(Direction::West),
+            _ => None,
+        }
+    }
+    /// Which way a move event goes on a hex maze, which has no east or west.
+    pub fn hex_direction(&self) -> Option<HexDirection> {
+        match self {
+            MazeEvent::MoveN => Some(HexDirection::North),
+            MazeEvent::MoveNE => Some(HexDirection::NorthEast),
+            MazeEvent::MoveSE => Some(HexDirection::SouthEast),
+            MazeEvent::MoveS => Some(HexDirection::South),
+            MazeEvent::MoveSW => Some(HexDirection::SouthWest),
+            MazeEvent::MoveNW => Some(HexDirection::NorthWest),
+            _ => None,
+        }
+    }
+}
+
 impl From<Event> for MazeEvent {
     fn from(val: Event) -> Self {
         match val {
@@ -88,6 +111,47 @@ impl From<Event> for MazeEvent {
                 code: KeyCode::Char('s'),
                 ..
             }) => MazeEvent::MoveS,
+            // the diagonals are the roguelike keys, or the corners of the number pad
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('u'),
+                ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('9'),
+                ..
+            }) => MazeEvent::MoveNE,
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('n'),
+                ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('3'),
+                ..
+            }) => MazeEvent::MoveSE,
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('b'),
+                ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('1'),
+                ..
+            }) => MazeEvent::MoveSW,
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('y'),
+                ..
+            })
+            | Event::Key(KeyEvent {
+                code: KeyCode::Char('7'),
+                ..
+            }) => MazeEvent::MoveNW,
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('<'),
+                ..
+            }) => MazeEvent::Up,
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('>'),
+                ..
+            }) => MazeEvent::Down,
             Event::Key(KeyEvent {
                 code: KeyCode::Enter,
                 ..
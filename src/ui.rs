@@ -1,12 +1,22 @@
 use crate::{
     Direction,
-    maze::{DoorState, Room},
+    grid::{Ix, Size},
+    maze::{
+        DoorState, MoveError, Room, hex::HexDirection, keys::KeyId, polar::PolarDirection,
+        weave::RoomKind,
+    },
 };
 use ratatui::{
     Frame,
-    style::Color,
-    widgets::canvas::{Canvas, Context, Line, Painter, Shape},
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Stylize},
+    text::{self, Span},
+    widgets::{
+        Paragraph,
+        canvas::{Canvas, Context, Line, Painter, Shape},
+    },
 };
+use std::f64::consts::PI;
 
 pub const MIN_X: f64 = -200.0;
 pub const MAX_X: f64 = 200.0;
@@ -19,9 +29,98 @@ pub const BG_COLOR: Color = Color::Black;
 pub const WALL_COLOR: Color = Color::Green;
 pub const HIDDEN_WALL_COLOR: Color = Color::Gray;
 pub const DOOR_COLOR: Color = Color::Red;
-pub fn render_maze<const N_ROWS: usize, const N_COLS: usize, F>(
-    f: F,
-) -> impl for<'a> FnOnce(&'a mut Frame)
+pub const ONE_WAY_COLOR: Color = Color::LightBlue;
+pub const STAIRS_COLOR: Color = Color::White;
+pub const WRAP_COLOR: Color = Color::LightMagenta;
+/// Locked doors, and the keys that open them, are drawn in these colours in
+/// turn, so it's clear which key goes with which door. None of them is close
+/// to the colours of walls, closed doors, one-way doors or wrapped edges.
+pub const KEY_COLORS: [Color; 4] = [
+    Color::Yellow,
+    Color::Cyan,
+    Color::White,
+    // orange, on terminals with 256 colours
+    Color::Indexed(208),
+];
+
+pub fn key_color(key: KeyId) -> Color {
+    KEY_COLORS[key % KEY_COLORS.len()]
+}
+
+/// A key, numbered and coloured to match the doors it opens.
+pub fn key_label(key: KeyId) -> text::Line<'static> {
+    text::Line::from(vec![
+        Span::raw("\u{1f511}"),
+        Span::raw(key.to_string()).fg(key_color(key)),
+    ])
+}
+
+/// Canvas bounds that fit a maze of `size`. Up to 7x7 it's drawn at full
+/// scale; bigger mazes are shrunk to fit.
+pub fn maze_bounds(size: Size) -> ([f64; 2], [f64; 2]) {
+    (
+        [MIN_X, MAX_X.max(MIN_X + ROOM_SIZE * size.n_cols as f64)],
+        [MIN_Y.min(MAX_Y - ROOM_SIZE * size.n_rows as f64), MAX_Y],
+    )
+}
+
+/// The distance from the middle of a hex room to its corners.
+pub const HEX_RADIUS: f64 = 30.0;
+
+/// The middle of hex room `ix` on the canvas. Each column is half a room's
+/// width further east than the last, and odd columns sit half a room lower.
+pub fn hex_centre(ix: Ix) -> (f64, f64) {
+    let height = 3f64.sqrt() * HEX_RADIUS;
+    let shift = if ix.x() % 2 == 1 { height / 2.0 } else { 0.0 };
+    (
+        MIN_X + HEX_RADIUS * (1.0 + 1.5 * ix.x() as f64),
+        MAX_Y - height * (0.5 + ix.y() as f64) - shift,
+    )
+}
+
+/// Like `maze_bounds`, for a hex maze.
+pub fn hex_bounds(size: Size) -> ([f64; 2], [f64; 2]) {
+    let width = HEX_RADIUS * (1.5 * size.n_cols as f64 + 0.5);
+    let height = 3f64.sqrt() * HEX_RADIUS * (size.n_rows as f64 + 0.5);
+    (
+        [MIN_X, MAX_X.max(MIN_X + width)],
+        [MIN_Y.min(MAX_Y - height), MAX_Y],
+    )
+}
+
+/// How deep each ring of a polar maze is, and the radius of the room in the
+/// middle. Polar mazes are drawn around the middle of the canvas.
+pub const RING_DEPTH: f64 = ROOM_SIZE;
+
+/// The angle of the anticlockwise edge of room `cell` of a ring of `len` rooms,
+/// which are numbered clockwise from north.
+fn polar_angle(len: usize, cell: f64) -> f64 {
+    PI / 2.0 - 2.0 * PI * cell / len as f64
+}
+
+fn polar_point(radius: f64, angle: f64) -> (f64, f64) {
+    (
+        RING_DEPTH * radius * angle.cos(),
+        RING_DEPTH * radius * angle.sin(),
+    )
+}
+
+/// The middle of room `cell` of ring `ring`, which has `len` rooms.
+pub fn polar_centre(ring: usize, len: usize, cell: usize) -> (f64, f64) {
+    if ring == 0 {
+        (0.0, 0.0)
+    } else {
+        polar_point(ring as f64 + 0.5, polar_angle(len, cell as f64 + 0.5))
+    }
+}
+
+/// Like `maze_bounds`, for a polar maze of `rings` rings.
+pub fn polar_bounds(rings: usize) -> ([f64; 2], [f64; 2]) {
+    let radius = MAX_X.max(RING_DEPTH * rings as f64);
+    ([-radius, radius], [-radius, radius])
+}
+
+pub fn render_maze<F>(f: F) -> impl for<'a> FnOnce(&'a mut Frame)
 where
     F: Fn(&mut Context),
 {
@@ -33,6 +132,26 @@ where
     |frame: &mut Frame| frame.render_widget(widget, frame.area())
 }
 
+/// Splits off a one-line strip at the bottom of `area` for a `footer`.
+pub fn footer_layout(area: Rect) -> [Rect; 2] {
+    Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(area)
+}
+
+/// The footer text: the maze's `info`, after why the last move failed if it did.
+pub fn status(info: &str, blocked: Option<MoveError>) -> String {
+    match blocked {
+        Some(e) => format!("{e} | {info}"),
+        None => info.to_string(),
+    }
+}
+
+pub fn footer(text: &str) -> Paragraph<'_> {
+    Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .fg(WALL_COLOR)
+        .bg(BG_COLOR)
+}
+
 #[derive(Debug)]
 pub struct RoomView<'a> {
     pub x: f64,
@@ -42,6 +161,18 @@ pub struct RoomView<'a> {
 
 impl<'a> Shape for RoomView<'a> {
     fn draw(&self, painter: &mut Painter<'_, '_>) {
+        if self.room.kind != RoomKind::Plain {
+            return self.draw_crossing(painter);
+        }
+        // walls on an edge that wraps round are marked, so it's clear the doors
+        // in them lead somewhere
+        let wall = |dir| {
+            if self.room.wraps(dir) {
+                WRAP_COLOR
+            } else {
+                WALL_COLOR
+            }
+        };
         let lines: &[Line] = &[
             // north
             Line {
@@ -49,7 +180,7 @@ impl<'a> Shape for RoomView<'a> {
                 y1: self.y,
                 x2: self.x + SEG_LEN * 2.0,
                 y2: self.y,
-                color: WALL_COLOR,
+                color: wall(Direction::North),
             },
             Line {
                 x1: self.x + SEG_LEN * 2.0,
@@ -63,7 +194,7 @@ impl<'a> Shape for RoomView<'a> {
                 y1: self.y,
                 x2: self.x + SEG_LEN * 7.0,
                 y2: self.y,
-                color: WALL_COLOR,
+                color: wall(Direction::North),
             },
             // west
             Line {
@@ -71,7 +202,7 @@ impl<'a> Shape for RoomView<'a> {
                 y1: self.y,
                 x2: self.x,
                 y2: self.y - SEG_LEN * 3.0,
-                color: WALL_COLOR,
+                color: wall(Direction::West),
             },
             Line {
                 x1: self.x,
@@ -85,7 +216,7 @@ impl<'a> Shape for RoomView<'a> {
                 y1: self.y - SEG_LEN * 5.0,
                 x2: self.x,
                 y2: self.y - SEG_LEN * 7.0,
-                color: WALL_COLOR,
+                color: wall(Direction::West),
             },
             // south
             Line {
@@ -93,7 +224,7 @@ impl<'a> Shape for RoomView<'a> {
                 y1: self.y - SEG_LEN * 7.0,
                 x2: self.x + SEG_LEN * 2.0,
                 y2: self.y - SEG_LEN * 7.0,
-                color: WALL_COLOR,
+                color: wall(Direction::South),
             },
             Line {
                 x1: self.x + SEG_LEN * 2.0,
@@ -107,7 +238,7 @@ impl<'a> Shape for RoomView<'a> {
                 y1: self.y - SEG_LEN * 7.0,
                 x2: self.x + SEG_LEN * 7.0,
                 y2: self.y - SEG_LEN * 7.0,
-                color: WALL_COLOR,
+                color: wall(Direction::South),
             },
             // east
             Line {
@@ -115,7 +246,7 @@ impl<'a> Shape for RoomView<'a> {
                 y1: self.y,
                 x2: self.x + SEG_LEN * 7.0,
                 y2: self.y - SEG_LEN * 3.0,
-                color: WALL_COLOR,
+                color: wall(Direction::East),
             },
             Line {
                 x1: self.x + SEG_LEN * 7.0,
@@ -129,12 +260,350 @@ impl<'a> Shape for RoomView<'a> {
                 y1: self.y - SEG_LEN * 5.0,
                 x2: self.x + SEG_LEN * 7.0,
                 y2: self.y - SEG_LEN * 7.0,
-                color: WALL_COLOR,
+                color: wall(Direction::East),
             },
         ];
         for line in lines {
             line.draw(painter)
         }
+        for (dir, st) in &self.room.doors {
+            if st == DoorState::OneWayOut {
+                self.draw_arrow(painter, dir);
+            }
+        }
+    }
+}
+
+impl<'a> RoomView<'a> {
+    /// A crossing: the walls of the corridor on top run right through the
+    /// room, and the walls of the one underneath stop short of them, leaving a
+    /// gap where it goes under.
+    fn draw_crossing(&self, painter: &mut Painter<'_, '_>) {
+        let (x, y) = (self.x, self.y);
+        let wall = |dir| {
+            if self.room.wraps(dir) {
+                WRAP_COLOR
+            } else {
+                WALL_COLOR
+            }
+        };
+        let line = |x1, y1, x2, y2, color| Line {
+            x1,
+            y1,
+            x2,
+            y2,
+            color,
+        };
+        let mut lines = vec![
+            // the corners, either side of each door
+            line(x, y, x + SEG_LEN * 2.0, y, wall(Direction::North)),
+            line(
+                x + SEG_LEN * 5.0,
+                y,
+                x + SEG_LEN * 7.0,
+                y,
+                wall(Direction::North),
+            ),
+            line(x, y, x, y - SEG_LEN * 3.0, wall(Direction::West)),
+            line(
+                x,
+                y - SEG_LEN * 5.0,
+                x,
+                y - SEG_LEN * 7.0,
+                wall(Direction::West),
+            ),
+            line(
+                x,
+                y - SEG_LEN * 7.0,
+                x + SEG_LEN * 2.0,
+                y - SEG_LEN * 7.0,
+                wall(Direction::South),
+            ),
+            line(
+                x + SEG_LEN * 5.0,
+                y - SEG_LEN * 7.0,
+                x + SEG_LEN * 7.0,
+                y - SEG_LEN * 7.0,
+                wall(Direction::South),
+            ),
+            line(
+                x + SEG_LEN * 7.0,
+                y,
+                x + SEG_LEN * 7.0,
+                y - SEG_LEN * 3.0,
+                wall(Direction::East),
+            ),
+            line(
+                x + SEG_LEN * 7.0,
+                y - SEG_LEN * 5.0,
+                x + SEG_LEN * 7.0,
+                y - SEG_LEN * 7.0,
+                wall(Direction::East),
+            ),
+        ];
+        // the doors of the corridor on top, coloured like any other room's
+        let doors = &self.room.doors;
+        if self.room.kind == RoomKind::OverNorthSouth {
+            lines.push(line(
+                x + SEG_LEN * 2.0,
+                y,
+                x + SEG_LEN * 5.0,
+                y,
+                door_state_color(&doors.north),
+            ));
+            lines.push(line(
+                x + SEG_LEN * 2.0,
+                y - SEG_LEN * 7.0,
+                x + SEG_LEN * 5.0,
+                y - SEG_LEN * 7.0,
+                door_state_color(&doors.south),
+            ));
+        } else {
+            lines.push(line(
+                x,
+                y - SEG_LEN * 3.0,
+                x,
+                y - SEG_LEN * 5.0,
+                door_state_color(&doors.west),
+            ));
+            lines.push(line(
+                x + SEG_LEN * 7.0,
+                y - SEG_LEN * 3.0,
+                x + SEG_LEN * 7.0,
+                y - SEG_LEN * 5.0,
+                door_state_color(&doors.east),
+            ));
+        }
+        // the corridor on top is the width of a door, and the one underneath
+        // stops a segment short of it on either side
+        if self.room.kind == RoomKind::OverNorthSouth {
+            for wall_x in [x + SEG_LEN * 2.0, x + SEG_LEN * 5.0] {
+                lines.push(line(wall_x, y, wall_x, y - SEG_LEN * 7.0, WALL_COLOR));
+            }
+            for wall_y in [y - SEG_LEN * 3.0, y - SEG_LEN * 5.0] {
+                lines.push(line(x, wall_y, x + SEG_LEN, wall_y, WALL_COLOR));
+                lines.push(line(
+                    x + SEG_LEN * 6.0,
+                    wall_y,
+                    x + SEG_LEN * 7.0,
+                    wall_y,
+                    WALL_COLOR,
+                ));
+            }
+        } else {
+            for wall_y in [y - SEG_LEN * 3.0, y - SEG_LEN * 5.0] {
+                lines.push(line(x, wall_y, x + SEG_LEN * 7.0, wall_y, WALL_COLOR));
+            }
+            for wall_x in [x + SEG_LEN * 2.0, x + SEG_LEN * 5.0] {
+                lines.push(line(wall_x, y, wall_x, y - SEG_LEN, WALL_COLOR));
+                lines.push(line(
+                    wall_x,
+                    y - SEG_LEN * 6.0,
+                    wall_x,
+                    y - SEG_LEN * 7.0,
+                    WALL_COLOR,
+                ));
+            }
+        }
+        for line in &lines {
+            line.draw(painter)
+        }
+        for (dir, st) in &self.room.doors {
+            if st == DoorState::OneWayOut {
+                self.draw_arrow(painter, dir);
+            }
+        }
+    }
+    /// The middle of the door on the `dir` side.
+    fn door_centre(&self, dir: Direction) -> (f64, f64) {
+        match dir {
+            Direction::North => (self.x + SEG_LEN * 3.5, self.y),
+            Direction::East => (self.x + SEG_LEN * 7.0, self.y - SEG_LEN * 4.0),
+            Direction::South => (self.x + SEG_LEN * 3.5, self.y - SEG_LEN * 7.0),
+            Direction::West => (self.x, self.y - SEG_LEN * 4.0),
+        }
+    }
+    /// An arrowhead across a one-way door, pointing the way it can be passed.
+    fn draw_arrow(&self, painter: &mut Painter<'_, '_>, dir: Direction) {
+        let (x, y) = self.door_centre(dir);
+        let (d_row, d_col) = dir.delta();
+        // canvas y goes up the screen, rows go down it
+        let (dx, dy) = (d_col as f64 * SEG_LEN, -d_row as f64 * SEG_LEN);
+        for side in [-1.0, 1.0] {
+            Line {
+                x1: x - dx + side * dy,
+                y1: y - dy + side * dx,
+                x2: x + dx,
+                y2: y + dy,
+                color: ONE_WAY_COLOR,
+            }
+            .draw(painter);
+        }
+    }
+}
+
+/// A hex room, with its doors in `HexDirection::ALL` order.
+#[derive(Debug)]
+pub struct HexRoomView {
+    pub x: f64,
+    pub y: f64,
+    pub doors: [Option<DoorState>; 6],
+}
+
+/// The ends of the `dir` side of the hex room around `x`, `y`, going
+/// anticlockwise.
+fn hex_side(x: f64, y: f64, dir: HexDirection) -> ((f64, f64), (f64, f64)) {
+    // the corners are numbered anticlockwise from the east one
+    let first = match dir {
+        HexDirection::NorthEast => 0.0,
+        HexDirection::North => 1.0,
+        HexDirection::NorthWest => 2.0,
+        HexDirection::SouthWest => 3.0,
+        HexDirection::South => 4.0,
+        HexDirection::SouthEast => 5.0,
+    };
+    let corner = |k: f64| {
+        let angle = PI / 3.0 * k;
+        (x + HEX_RADIUS * angle.cos(), y + HEX_RADIUS * angle.sin())
+    };
+    (corner(first), corner(first + 1.0))
+}
+
+impl Shape for HexRoomView {
+    fn draw(&self, painter: &mut Painter<'_, '_>) {
+        for (dir, door) in HexDirection::ALL.into_iter().zip(self.doors) {
+            let ((x1, y1), (x2, y2)) = hex_side(self.x, self.y, dir);
+            // the door takes up the same share of the side as on a square room
+            let at = |t: f64| (x1 + (x2 - x1) * t, y1 + (y2 - y1) * t);
+            let stops = [at(0.0), at(2.0 / 7.0), at(5.0 / 7.0), at(1.0)];
+            for (i, pair) in stops.windows(2).enumerate() {
+                Line {
+                    x1: pair[0].0,
+                    y1: pair[0].1,
+                    x2: pair[1].0,
+                    y2: pair[1].1,
+                    color: if i == 1 {
+                        door_state_color(&door)
+                    } else {
+                        WALL_COLOR
+                    },
+                }
+                .draw(painter);
+            }
+        }
+    }
+}
+
+/// The outline of a hex room that hasn't been seen yet.
+#[derive(Debug)]
+pub struct UnseenHexView {
+    pub x: f64,
+    pub y: f64,
+    pub hidden_walls: Vec<HexDirection>,
+}
+
+impl Shape for UnseenHexView {
+    fn draw(&self, painter: &mut Painter<'_, '_>) {
+        for &dir in self.hidden_walls.iter() {
+            let ((x1, y1), (x2, y2)) = hex_side(self.x, self.y, dir);
+            Line {
+                x1,
+                y1,
+                x2,
+                y2,
+                color: HIDDEN_WALL_COLOR,
+            }
+            .draw(painter);
+        }
+    }
+}
+
+/// How many pieces each side of a polar room is drawn in. It's a multiple of 7
+/// so the door can take up the same share of the side as on a square room.
+const POLAR_SIDE_STEPS: usize = 21;
+
+/// Points along the `dir` side of room `cell` of ring `ring`, which has `len`
+/// rooms, each split into `outward` rooms by the next ring out.
+fn polar_side(
+    ring: usize,
+    len: usize,
+    cell: usize,
+    outward: usize,
+    dir: PolarDirection,
+) -> Vec<(f64, f64)> {
+    let (ring, cell) = (ring as f64, cell as f64);
+    let at = |t: f64| match dir {
+        PolarDirection::Inward => polar_point(ring, polar_angle(len, cell + t)),
+        PolarDirection::Outward(k) => {
+            let n = outward.max(1);
+            let angle = polar_angle(len * n, cell * n as f64 + k as f64 + t);
+            polar_point(ring + 1.0, angle)
+        }
+        PolarDirection::Anticlockwise => polar_point(ring + t, polar_angle(len, cell)),
+        PolarDirection::Clockwise => polar_point(ring + t, polar_angle(len, cell + 1.0)),
+    };
+    (0..=POLAR_SIDE_STEPS)
+        .map(|i| at(i as f64 / POLAR_SIDE_STEPS as f64))
+        .collect()
+}
+
+fn draw_polyline(painter: &mut Painter<'_, '_>, points: &[(f64, f64)], color: Color) {
+    for pair in points.windows(2) {
+        Line {
+            x1: pair[0].0,
+            y1: pair[0].1,
+            x2: pair[1].0,
+            y2: pair[1].1,
+            color,
+        }
+        .draw(painter);
+    }
+}
+
+/// A room of a polar maze: room `cell` of ring `ring`, which has `len` rooms,
+/// each split into `outward` rooms by the next ring out.
+#[derive(Debug)]
+pub struct PolarRoomView {
+    pub ring: usize,
+    pub len: usize,
+    pub cell: usize,
+    pub outward: usize,
+    pub doors: Vec<(PolarDirection, Option<DoorState>)>,
+}
+
+impl Shape for PolarRoomView {
+    fn draw(&self, painter: &mut Painter<'_, '_>) {
+        let door_from = POLAR_SIDE_STEPS * 2 / 7;
+        let door_to = POLAR_SIDE_STEPS * 5 / 7;
+        for &(dir, door) in &self.doors {
+            let points = polar_side(self.ring, self.len, self.cell, self.outward, dir);
+            draw_polyline(painter, &points[..=door_from], WALL_COLOR);
+            draw_polyline(
+                painter,
+                &points[door_from..=door_to],
+                door_state_color(&door),
+            );
+            draw_polyline(painter, &points[door_to..], WALL_COLOR);
+        }
+    }
+}
+
+/// The outline of a polar room that hasn't been seen yet.
+#[derive(Debug)]
+pub struct UnseenPolarView {
+    pub ring: usize,
+    pub len: usize,
+    pub cell: usize,
+    pub outward: usize,
+    pub hidden_walls: Vec<PolarDirection>,
+}
+
+impl Shape for UnseenPolarView {
+    fn draw(&self, painter: &mut Painter<'_, '_>) {
+        for &dir in self.hidden_walls.iter() {
+            let points = polar_side(self.ring, self.len, self.cell, self.outward, dir);
+            draw_polyline(painter, &points, HIDDEN_WALL_COLOR);
+        }
     }
 }
 
@@ -213,5 +682,7 @@ fn door_state_color(ds: &Option<DoorState>) -> Color {
         None => WALL_COLOR,
         Some(DoorState::Open) => BG_COLOR,
         Some(DoorState::Closed) => DOOR_COLOR,
+        Some(DoorState::Locked(key)) => key_color(*key),
+        Some(DoorState::OneWayOut | DoorState::OneWayIn) => ONE_WAY_COLOR,
     }
 }